@@ -0,0 +1,259 @@
+// Event-driven global input capture, shared by the GUI's keybind-capture flow and the
+// macro engine's trigger polling. Earlier this was a device_query poll every 10ms, which
+// could miss fast taps and burned CPU even while idle. This instead spawns a thread that
+// blocks on real input events (evdev on Linux) and fans them out over one mpsc channel per
+// consumer, so a bind can complete - and a trigger can fire - even while the other side's
+// window has focus.
+use device_query::Keycode;
+use std::sync::mpsc::{self, Receiver};
+use std::thread;
+
+#[derive(Clone, Copy, Debug)]
+pub enum CaptureEvent {
+    KeyDown(Keycode),
+    KeyUp(Keycode),
+    MouseDown(usize),
+    MouseUp(usize),
+    Scroll(ScrollDirection),
+}
+
+// A single wheel notch, vertical or horizontal (tilt). Mirrors winit's MouseScrollDelta /
+// Amethyst's scroll-direction handling in treating the wheel as a first-class input rather
+// than something only read via a raw per-frame delta.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ScrollDirection {
+    Up,
+    Down,
+    Left,
+    Right,
+}
+
+// Start the backend thread(s) and return the receiving end of the event channel.
+pub fn spawn() -> Receiver<CaptureEvent> {
+    spawn_n(1).pop().unwrap()
+}
+
+// Same backend, but fanned out to `n` independent receivers - lets the GUI's keybind
+// capture and the macro engine's trigger polling share one set of evdev readers instead of
+// each opening their own, so a bind can complete (and a trigger can fire) even while the
+// other side's window has focus.
+pub fn spawn_n(n: usize) -> Vec<Receiver<CaptureEvent>> {
+    let mut txs = Vec::with_capacity(n);
+    let mut rxs = Vec::with_capacity(n);
+    for _ in 0..n {
+        let (tx, rx) = mpsc::channel();
+        txs.push(tx);
+        rxs.push(rx);
+    }
+    #[cfg(target_os = "linux")]
+    thread::spawn(move || linux::run(txs));
+    #[cfg(not(target_os = "linux"))]
+    thread::spawn(move || fallback::run(txs));
+    rxs
+}
+
+// True event-driven backend: block on evdev's fetch_events() for every readable input
+// device instead of polling, the same way xremap/mki's global hooks stay idle until the
+// kernel actually has something for them.
+#[cfg(target_os = "linux")]
+mod linux {
+    use super::CaptureEvent;
+    use device_query::Keycode;
+    use evdev::Device;
+    use std::fs;
+    use std::path::Path;
+    use std::sync::mpsc::Sender;
+    use std::thread;
+
+    const EV_KEY: u16 = 1;
+    const EV_REL: u16 = 2;
+    const REL_HWHEEL: u16 = 6;
+    const REL_WHEEL: u16 = 8;
+
+    pub fn run(txs: Vec<Sender<CaptureEvent>>) {
+        let devices = open_input_devices();
+        if devices.is_empty() {
+            eprintln!("⚠️  No evdev input devices could be opened for capture.");
+            eprintln!("   Add yourself to the 'input' group (sudo usermod -aG input $USER, then log out/in).");
+            return;
+        }
+        // One blocking reader per device; every device forwards onto every consumer's
+        // channel, so keyboard, mouse and wheel events interleave in the order they
+        // actually happened, for each consumer independently.
+        for mut device in devices {
+            let txs = txs.clone();
+            thread::spawn(move || loop {
+                match device.fetch_events() {
+                    Ok(events) => {
+                        for event in events {
+                            let event_type = event.event_type().0;
+                            if event_type == EV_REL {
+                                let direction = match event.code() {
+                                    REL_WHEEL if event.value() > 0 => Some(CaptureEvent::Scroll(super::ScrollDirection::Up)),
+                                    REL_WHEEL if event.value() < 0 => Some(CaptureEvent::Scroll(super::ScrollDirection::Down)),
+                                    REL_HWHEEL if event.value() > 0 => Some(CaptureEvent::Scroll(super::ScrollDirection::Right)),
+                                    REL_HWHEEL if event.value() < 0 => Some(CaptureEvent::Scroll(super::ScrollDirection::Left)),
+                                    _ => None,
+                                };
+                                if let Some(captured) = direction {
+                                    for tx in &txs {
+                                        let _ = tx.send(captured);
+                                    }
+                                }
+                                continue;
+                            }
+                            if event_type != EV_KEY || event.value() == 2 {
+                                continue; // not a key/button event, or just auto-repeat
+                            }
+                            let code = event.code();
+                            let pressed = event.value() != 0;
+                            if let Some(key_code) = translate_key(code) {
+                                let captured = if pressed { CaptureEvent::KeyDown(key_code) } else { CaptureEvent::KeyUp(key_code) };
+                                for tx in &txs {
+                                    let _ = tx.send(captured);
+                                }
+                            } else if let Some(idx) = translate_mouse_button(code) {
+                                let captured = if pressed { CaptureEvent::MouseDown(idx) } else { CaptureEvent::MouseUp(idx) };
+                                for tx in &txs {
+                                    let _ = tx.send(captured);
+                                }
+                            }
+                        }
+                    }
+                    Err(_) => break, // device unplugged or permission revoked
+                }
+            });
+        }
+    }
+
+    fn open_input_devices() -> Vec<Device> {
+        let mut devices = Vec::new();
+        let input_dir = Path::new("/dev/input");
+        if let Ok(entries) = fs::read_dir(input_dir) {
+            for entry in entries.flatten() {
+                let path = entry.path();
+                if path.file_name().and_then(|n| n.to_str()).map_or(false, |n| n.starts_with("event")) {
+                    if let Ok(device) = Device::open(&path) {
+                        devices.push(device);
+                    }
+                }
+            }
+        }
+        devices
+    }
+
+    // Mouse button codes (from linux/input-event-codes.h) translated to the same 1-based
+    // indices device_query/pynput use (button8/button9 for the side buttons), so downstream
+    // capture logic doesn't care which backend an event came from.
+    fn translate_mouse_button(code: u16) -> Option<usize> {
+        Some(match code {
+            0x110 => 1, // BTN_LEFT
+            0x111 => 2, // BTN_RIGHT
+            0x112 => 3, // BTN_MIDDLE
+            0x113 => 8, // BTN_SIDE
+            0x114 => 9, // BTN_EXTRA
+            _ => return None,
+        })
+    }
+
+    // Keys this macro's keybinds support (letters, digits, F-keys, Space, Dot, the
+    // modifiers chords are built from, and the navigation keys egui_key_name/
+    // physical_keycode_from_string also resolve) need a translation - see
+    // SharedConfig::physical_keycode_from_string for the matching cross-platform table.
+    fn translate_key(code: u16) -> Option<Keycode> {
+        use Keycode::*;
+        Some(match code {
+            30 => A, 48 => B, 46 => C, 32 => D, 18 => E, 33 => F, 34 => G, 35 => H,
+            23 => I, 36 => J, 37 => K, 38 => L, 50 => M, 49 => N, 24 => O, 25 => P,
+            16 => Q, 19 => R, 31 => S, 20 => T, 22 => U, 47 => V, 17 => W, 45 => X,
+            21 => Y, 44 => Z,
+            11 => Key0, 2 => Key1, 3 => Key2, 4 => Key3, 5 => Key4, 6 => Key5,
+            7 => Key6, 8 => Key7, 9 => Key8, 10 => Key9,
+            59 => F1, 60 => F2, 61 => F3, 62 => F4, 63 => F5, 64 => F6,
+            65 => F7, 66 => F8, 67 => F9, 68 => F10, 87 => F11, 88 => F12,
+            57 => Space,
+            52 => Dot,
+            1 => Escape,
+            29 => LControl,
+            97 => RControl,
+            42 => LShift,
+            54 => RShift,
+            56 => LAlt,
+            100 => RAlt,
+            28 => Enter,
+            15 => Tab,
+            14 => Backspace,
+            110 => Insert,
+            111 => Delete,
+            102 => Home,
+            107 => End,
+            104 => PageUp,
+            109 => PageDown,
+            103 => Up,
+            108 => Down,
+            105 => Left,
+            106 => Right,
+            _ => return None,
+        })
+    }
+}
+
+// Non-Linux fallback: there's no winapi low-level keyboard/mouse hook wired up yet, so this
+// keeps polling device_query, just tightened to remove the old artifacts (no artificial
+// 200ms "ignore the starting click" delay, no alive-logging) - the channel's receiver
+// blocks either way, so callers can't tell the difference. device_query has no wheel
+// support, so this backend never emits CaptureEvent::Scroll - scroll-bound triggers just
+// won't be capturable or active on these platforms yet.
+#[cfg(not(target_os = "linux"))]
+mod fallback {
+    use super::CaptureEvent;
+    use device_query::{DeviceQuery, DeviceState};
+    use std::collections::HashSet;
+    use std::sync::mpsc::Sender;
+    use std::thread;
+    use std::time::Duration;
+
+    pub fn run(txs: Vec<Sender<CaptureEvent>>) {
+        let device_state = DeviceState::new();
+        let mut last_keys = HashSet::new();
+        let mut last_buttons: Vec<bool> = Vec::new();
+
+        loop {
+            let keys = device_state.get_keys();
+            let keys_set: HashSet<_> = keys.iter().cloned().collect();
+            let mouse = device_state.get_mouse();
+
+            for key in keys_set.difference(&last_keys) {
+                let captured = CaptureEvent::KeyDown(*key);
+                for tx in &txs {
+                    let _ = tx.send(captured);
+                }
+            }
+            for key in last_keys.difference(&keys_set) {
+                let captured = CaptureEvent::KeyUp(*key);
+                for tx in &txs {
+                    let _ = tx.send(captured);
+                }
+            }
+
+            for (idx, &pressed) in mouse.button_pressed.iter().enumerate() {
+                let was_pressed = last_buttons.get(idx).copied().unwrap_or(false);
+                if pressed && !was_pressed {
+                    let captured = CaptureEvent::MouseDown(idx);
+                    for tx in &txs {
+                        let _ = tx.send(captured);
+                    }
+                } else if !pressed && was_pressed {
+                    let captured = CaptureEvent::MouseUp(idx);
+                    for tx in &txs {
+                        let _ = tx.send(captured);
+                    }
+                }
+            }
+
+            last_keys = keys_set;
+            last_buttons = mouse.button_pressed;
+            thread::sleep(Duration::from_millis(2));
+        }
+    }
+}
@@ -1,7 +1,386 @@
 use device_query::Keycode;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::path::Path;
+
+// Named profiles each live as their own RON file under this directory, e.g.
+// "profiles/Contagion.ron", so a whole macro setup (timings + keybinds) can be swapped
+// out from the GUI instead of re-entering every slider and key.
+pub const PROFILES_DIR: &str = "profiles";
+pub const DEFAULT_PROFILE: &str = "default";
+
+#[derive(Debug)]
+pub enum ConfigError {
+    Io(std::io::Error),
+    Parse(toml::de::Error),
+    Serialize(toml::ser::Error),
+    RonParse(ron::de::SpannedError),
+    RonSerialize(ron::Error),
+    Json(serde_json::Error),
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigError::Io(e) => write!(f, "config io error: {}", e),
+            ConfigError::Parse(e) => write!(f, "config parse error: {}", e),
+            ConfigError::Serialize(e) => write!(f, "config serialize error: {}", e),
+            ConfigError::RonParse(e) => write!(f, "profile parse error: {}", e),
+            ConfigError::RonSerialize(e) => write!(f, "profile serialize error: {}", e),
+            ConfigError::Json(e) => write!(f, "json parse error: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+impl From<std::io::Error> for ConfigError {
+    fn from(e: std::io::Error) -> Self {
+        ConfigError::Io(e)
+    }
+}
+
+impl From<toml::de::Error> for ConfigError {
+    fn from(e: toml::de::Error) -> Self {
+        ConfigError::Parse(e)
+    }
+}
+
+impl From<toml::ser::Error> for ConfigError {
+    fn from(e: toml::ser::Error) -> Self {
+        ConfigError::Serialize(e)
+    }
+}
+
+impl From<ron::de::SpannedError> for ConfigError {
+    fn from(e: ron::de::SpannedError) -> Self {
+        ConfigError::RonParse(e)
+    }
+}
+
+impl From<ron::Error> for ConfigError {
+    fn from(e: ron::Error) -> Self {
+        ConfigError::RonSerialize(e)
+    }
+}
+
+impl From<serde_json::Error> for ConfigError {
+    fn from(e: serde_json::Error) -> Self {
+        ConfigError::Json(e)
+    }
+}
+
+pub type ConfigResult<T> = Result<T, ConfigError>;
+
+bitflags::bitflags! {
+    // Which modifier keys must be held alongside a Hotkey's base key.
+    #[derive(Default)]
+    pub struct Modifiers: u8 {
+        const CTRL  = 0b0001;
+        const SHIFT = 0b0010;
+        const ALT   = 0b0100;
+        const META  = 0b1000;
+    }
+}
+
+// A base key plus the exact set of modifiers that must be held with it,
+// e.g. "Ctrl+Shift+E" -> Hotkey { code: Keycode::E, modifiers: CTRL | SHIFT }.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Hotkey {
+    pub code: Keycode,
+    pub modifiers: Modifiers,
+}
+
+// Query the currently held modifier keys from device_query, OR-ing the left/right pairs
+// together so a bind like "Ctrl+E" fires regardless of which Ctrl key the user pressed.
+pub fn held_modifiers(keys: &[Keycode]) -> Modifiers {
+    let mut mods = Modifiers::empty();
+    if keys.contains(&Keycode::LControl) || keys.contains(&Keycode::RControl) {
+        mods |= Modifiers::CTRL;
+    }
+    if keys.contains(&Keycode::LShift) || keys.contains(&Keycode::RShift) {
+        mods |= Modifiers::SHIFT;
+    }
+    if keys.contains(&Keycode::LAlt) || keys.contains(&Keycode::RAlt) {
+        mods |= Modifiers::ALT;
+    }
+    mods
+}
+
+impl Hotkey {
+    pub fn new(code: Keycode, modifiers: Modifiers) -> Self {
+        Self { code, modifiers }
+    }
+
+    pub fn bare(code: Keycode) -> Self {
+        Self { code, modifiers: Modifiers::empty() }
+    }
+}
+
+// Physical keyboard layout, used to translate between a physical Keycode and the
+// character the user actually sees printed on that key. Follows the neovide pattern
+// of (de)serializing to a lowercase name so it reads nicely in a hand-edited config.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum KeyboardLayout {
+    #[default]
+    Qwerty,
+    Azerty,
+    Qwertz,
+    Dvorak,
+}
+
+impl Serialize for KeyboardLayout {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let name = match self {
+            KeyboardLayout::Qwerty => "qwerty",
+            KeyboardLayout::Azerty => "azerty",
+            KeyboardLayout::Qwertz => "qwertz",
+            KeyboardLayout::Dvorak => "dvorak",
+        };
+        serializer.serialize_str(name)
+    }
+}
+
+impl<'de> Deserialize<'de> for KeyboardLayout {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let name = String::deserialize(deserializer)?;
+        Ok(match name.to_lowercase().as_str() {
+            "azerty" => KeyboardLayout::Azerty,
+            "qwertz" => KeyboardLayout::Qwertz,
+            "dvorak" => KeyboardLayout::Dvorak,
+            // Unknown layout token - default to Qwerty rather than erroring out.
+            _ => KeyboardLayout::Qwerty,
+        })
+    }
+}
+
+// A mouse-driven macro trigger: a plain button, a scroll-wheel tick, or a button
+// held while the pointer is moving (drag). Modeled on the MouseEventKind taxonomy
+// used by terminal input layers like helix's.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum MouseTrigger {
+    Button(usize),
+    ScrollUp,
+    ScrollDown,
+    // Horizontal wheel tilt, where the mouse reports one (most don't).
+    ScrollLeft,
+    ScrollRight,
+    Drag(usize),
+}
+
+impl Default for MouseTrigger {
+    fn default() -> Self {
+        MouseTrigger::Button(8)
+    }
+}
+
+impl Serialize for MouseTrigger {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&Self::to_tag(self))
+    }
+}
+
+impl<'de> Deserialize<'de> for MouseTrigger {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        Ok(Self::from_str(&s))
+    }
+}
+
+impl MouseTrigger {
+    pub fn to_tag(&self) -> String {
+        match self {
+            MouseTrigger::Button(idx) => format!("button:{}", idx),
+            MouseTrigger::ScrollUp => "scroll_up".to_string(),
+            MouseTrigger::ScrollDown => "scroll_down".to_string(),
+            MouseTrigger::ScrollLeft => "scroll_left".to_string(),
+            MouseTrigger::ScrollRight => "scroll_right".to_string(),
+            MouseTrigger::Drag(idx) => format!("drag:{}", idx),
+        }
+    }
+
+    pub fn from_str(s: &str) -> Self {
+        if let Some(idx) = s.strip_prefix("button:").and_then(|n| n.parse().ok()) {
+            return MouseTrigger::Button(idx);
+        }
+        if let Some(idx) = s.strip_prefix("drag:").and_then(|n| n.parse().ok()) {
+            return MouseTrigger::Drag(idx);
+        }
+        match s {
+            "scroll_up" => MouseTrigger::ScrollUp,
+            "scroll_down" => MouseTrigger::ScrollDown,
+            "scroll_left" => MouseTrigger::ScrollLeft,
+            "scroll_right" => MouseTrigger::ScrollRight,
+            // Unrecognized token - fall back to the default macro trigger button rather than panicking.
+            _ => MouseTrigger::default(),
+        }
+    }
+}
+
+// A gamepad-driven macro trigger: a named button (gilrs::Button's Debug name, e.g. "South")
+// or an analog axis (gilrs::Axis's Debug name) crossing a configurable deadzone (e.g. RightZ > 0.6).
+#[derive(Clone, PartialEq, Debug)]
+pub enum GamepadTrigger {
+    Button(String),
+    AxisAbove(String, f32),
+}
+
+impl fmt::Display for GamepadTrigger {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GamepadTrigger::Button(name) => write!(f, "pad:{}", name),
+            GamepadTrigger::AxisAbove(name, threshold) => write!(f, "pad:{}>{}", name, threshold),
+        }
+    }
+}
+
+impl GamepadTrigger {
+    // Parse the "pad:South" / "pad:RightZ>0.6" tags captured in the GUI.
+    pub fn from_str(s: &str) -> Option<Self> {
+        let body = s.strip_prefix("pad:")?;
+        if let Some((name, threshold)) = body.split_once('>') {
+            let threshold: f32 = threshold.parse().ok()?;
+            Some(GamepadTrigger::AxisAbove(name.to_string(), threshold))
+        } else {
+            Some(GamepadTrigger::Button(body.to_string()))
+        }
+    }
+}
+
+// Either a mouse trigger or a gamepad trigger, so a single keybind field can be satisfied
+// by whichever input device the user binds it from. Serializes as a plain string - mouse
+// triggers keep their existing "button:N"/"scroll_up"/"drag:N" form, gamepad ones are
+// tagged "pad:...".
+#[derive(Clone, PartialEq, Debug)]
+pub enum ActivationTrigger {
+    Mouse(MouseTrigger),
+    Gamepad(GamepadTrigger),
+}
+
+impl Default for ActivationTrigger {
+    fn default() -> Self {
+        ActivationTrigger::Mouse(MouseTrigger::default())
+    }
+}
+
+impl fmt::Display for ActivationTrigger {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ActivationTrigger::Mouse(m) => write!(f, "{}", m.to_tag()),
+            ActivationTrigger::Gamepad(g) => write!(f, "{}", g),
+        }
+    }
+}
+
+impl Serialize for ActivationTrigger {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+impl<'de> Deserialize<'de> for ActivationTrigger {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        Ok(Self::from_tag(&s))
+    }
+}
+
+impl ActivationTrigger {
+    // Parse a bare trigger tag ("button:8", "scroll_up", "pad:South", ...) with no
+    // modifier prefix - the part Chord::from_str hands off to this once it's stripped out
+    // any leading "Ctrl+"/"Shift+"/... tokens.
+    pub fn from_tag(s: &str) -> Self {
+        if s.starts_with("pad:") {
+            GamepadTrigger::from_str(s).map(ActivationTrigger::Gamepad).unwrap_or_default()
+        } else {
+            ActivationTrigger::Mouse(MouseTrigger::from_str(s))
+        }
+    }
+}
+
+// A mouse/gamepad trigger combined with the keyboard modifiers that must also be held -
+// the ActivationTrigger counterpart of Hotkey, so a macro button can require e.g. Ctrl the
+// same way a keyboard hotkey can (e.g. "Ctrl+button:8"). Serializes the same way Hotkey's
+// string form does: modifier tokens, "+"-joined, followed by the trigger's own tag.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Chord {
+    pub trigger: ActivationTrigger,
+    pub modifiers: Modifiers,
+}
+
+impl Default for Chord {
+    fn default() -> Self {
+        Self { trigger: ActivationTrigger::default(), modifiers: Modifiers::empty() }
+    }
+}
+
+// Plain ActivationTrigger -> Chord with no modifier requirement, for constructing defaults
+// without spelling out `Modifiers::empty()` at every call site.
+impl From<ActivationTrigger> for Chord {
+    fn from(trigger: ActivationTrigger) -> Self {
+        Self { trigger, modifiers: Modifiers::empty() }
+    }
+}
+
+impl fmt::Display for Chord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut parts = Vec::new();
+        if self.modifiers.contains(Modifiers::CTRL) {
+            parts.push("Ctrl");
+        }
+        if self.modifiers.contains(Modifiers::SHIFT) {
+            parts.push("Shift");
+        }
+        if self.modifiers.contains(Modifiers::ALT) {
+            parts.push("Alt");
+        }
+        if self.modifiers.contains(Modifiers::META) {
+            parts.push("Meta");
+        }
+        if parts.is_empty() {
+            write!(f, "{}", self.trigger)
+        } else {
+            write!(f, "{}+{}", parts.join("+"), self.trigger)
+        }
+    }
+}
+
+impl Serialize for Chord {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+impl<'de> Deserialize<'de> for Chord {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        Ok(Self::from_str(&s))
+    }
+}
+
+impl Chord {
+    // Every "+"-separated token except the last is a modifier, same convention as
+    // hotkey_from_string - the last token is the trigger tag itself, which never contains
+    // a "+" (see MouseTrigger/GamepadTrigger's to_tag/Display).
+    pub fn from_str(s: &str) -> Self {
+        let mut parts: Vec<&str> = s.split('+').map(str::trim).filter(|p| !p.is_empty()).collect();
+        let trigger_tag = parts.pop().unwrap_or_default();
+        let mut modifiers = Modifiers::empty();
+        for token in parts {
+            match token.to_uppercase().as_str() {
+                "CTRL" | "CONTROL" => modifiers |= Modifiers::CTRL,
+                "SHIFT" => modifiers |= Modifiers::SHIFT,
+                "ALT" => modifiers |= Modifiers::ALT,
+                "META" | "SUPER" | "WIN" => modifiers |= Modifiers::META,
+                _ => {} // Unknown modifier token - ignore rather than panic.
+            }
+        }
+        Self { trigger: ActivationTrigger::from_tag(trigger_tag), modifiers }
+    }
+}
 
 // Shared configuration that can be modified by GUI
-#[derive(Clone)]
+#[derive(Clone, Serialize, Deserialize)]
 pub struct SharedConfig {
     // Timing settings
     pub fps: f64,
@@ -22,11 +401,45 @@ pub struct SharedConfig {
     pub jump_key: String,
     pub emote_key: String,
     pub rapid_click_key: String,
+    // Alternative rapid-click trigger (mouse scroll/button or gamepad), alongside the
+    // keyboard key above - mirrors macro_button/macro_alt_button so a wheel notch can
+    // fire rapid-click the way competitive players bind bunny-hop to scroll.
+    #[serde(default)]
+    pub enable_rapid_click_alt: bool,
+    #[serde(default)]
+    pub rapid_click_alt_trigger: Chord,
     pub aim_button: usize,
     pub fire_button: usize,
-    pub macro_button: usize,
+    pub macro_button: Chord,
     pub enable_macro_alt: bool,
-    pub macro_alt_button: usize,
+    pub macro_alt_button: Chord,
+
+    // Recording settings. Recording start/stop itself is driven by the GUI's Record button
+    // (see gui.rs's RecordingSession), not a global hotkey.
+    pub playback_speed: f64,
+
+    // User-recorded macros, each bound to its own trigger (see NamedMacro).
+    #[serde(default)]
+    pub macros: Vec<crate::recorder::NamedMacro>,
+
+    // Active physical keyboard layout, for translating typed characters to physical keys
+    pub keyboard_layout: KeyboardLayout,
+
+    // Path to a user-authored macro sequence file (.toml or .json, see sequence::MacroSequence)
+    // that replaces the built-in Exodia Contagion routine. None (the default) keeps using the
+    // formula-driven Contagion sequence generated from the timing fields above.
+    #[serde(default)]
+    pub custom_sequence_path: Option<String>,
+
+    // Case-insensitive substring the background window watcher (see window.rs) matches
+    // against the active window's title/process name before letting any macro fire -
+    // replaces the old hardcoded "warframe" check so the tool can target any game.
+    #[serde(default = "default_target_window")]
+    pub target_window: String,
+}
+
+fn default_target_window() -> String {
+    "warframe".to_string()
 }
 
 impl Default for SharedConfig {
@@ -48,21 +461,62 @@ impl Default for SharedConfig {
             jump_key: "Space".to_string(),
             emote_key: ".".to_string(),
             rapid_click_key: "J".to_string(),
+            enable_rapid_click_alt: false,
+            rapid_click_alt_trigger: Chord::from(ActivationTrigger::Mouse(MouseTrigger::ScrollDown)),
             aim_button: 2,
             fire_button: 1,
-            macro_button: 8,  // Side button 1 (button8 in pynput)
+            macro_button: Chord::from(ActivationTrigger::Mouse(MouseTrigger::Button(8))),  // Side button 1 (button8 in pynput)
             enable_macro_alt: true,
-            macro_alt_button: 9,  // Side button 2 (button9 in pynput)
+            macro_alt_button: Chord::from(ActivationTrigger::Mouse(MouseTrigger::Button(9))),  // Side button 2 (button9 in pynput)
+            playback_speed: 1.0,
+            macros: Vec::new(),
+            keyboard_layout: KeyboardLayout::Qwerty,
+            custom_sequence_path: None,
+            target_window: default_target_window(),
         }
     }
 }
 
+// Letters whose printed character moves to a different physical key on these layouts,
+// relative to the US-QWERTY position that the rest of this table is built around.
+// Azerty swaps A<->Q and Z<->W; Qwertz swaps Y<->Z; Dvorak remaps the full home row etc.
+// Each entry maps a QWERTY-physical letter to the character that layout prints there.
+fn layout_letter_overrides(layout: KeyboardLayout) -> &'static [(char, char)] {
+    match layout {
+        KeyboardLayout::Qwerty => &[],
+        KeyboardLayout::Azerty => &[('A', 'Q'), ('Q', 'A'), ('Z', 'W'), ('W', 'Z'), ('M', ';')],
+        KeyboardLayout::Qwertz => &[('Y', 'Z'), ('Z', 'Y')],
+        KeyboardLayout::Dvorak => &[
+            ('Q', '\''), ('W', ','), ('E', '.'), ('R', 'P'), ('T', 'Y'),
+            ('Y', 'F'), ('U', 'G'), ('I', 'C'), ('O', 'R'), ('P', 'L'),
+            ('A', 'A'), ('S', 'O'), ('D', 'E'), ('F', 'U'), ('G', 'I'),
+            ('H', 'D'), ('J', 'H'), ('K', 'T'), ('L', 'N'),
+            ('Z', ';'), ('X', 'Q'), ('C', 'J'), ('V', 'K'), ('B', 'X'),
+            ('N', 'B'), ('M', 'M'),
+        ],
+    }
+}
+
 impl SharedConfig {
     // Convert Keycode to string representation
-    pub fn keycode_to_string(keycode: Keycode) -> String {
+    pub fn keycode_to_string(keycode: Keycode, layout: KeyboardLayout) -> String {
+        let physical = Self::physical_keycode_to_string(keycode);
+        if physical.len() == 1 {
+            let c = physical.chars().next().unwrap();
+            for (from, to) in layout_letter_overrides(layout) {
+                if *from == c {
+                    return to.to_string();
+                }
+            }
+        }
+        physical
+    }
+
+    // Physical key -> debug-derived label, independent of the active layout.
+    fn physical_keycode_to_string(keycode: Keycode) -> String {
         // Use Debug formatting and clean it up
         let debug_str = format!("{:?}", keycode);
-        
+
         // Handle common cases
         match keycode {
             Keycode::Space => "Space".to_string(),
@@ -115,6 +569,19 @@ impl SharedConfig {
             Keycode::F11 => "F11".to_string(),
             Keycode::F12 => "F12".to_string(),
             Keycode::Dot => ".".to_string(),
+            Keycode::Enter => "Enter".to_string(),
+            Keycode::Tab => "Tab".to_string(),
+            Keycode::Backspace => "Backspace".to_string(),
+            Keycode::Insert => "Insert".to_string(),
+            Keycode::Delete => "Delete".to_string(),
+            Keycode::Home => "Home".to_string(),
+            Keycode::End => "End".to_string(),
+            Keycode::PageUp => "PageUp".to_string(),
+            Keycode::PageDown => "PageDown".to_string(),
+            Keycode::Up => "ArrowUp".to_string(),
+            Keycode::Down => "ArrowDown".to_string(),
+            Keycode::Left => "ArrowLeft".to_string(),
+            Keycode::Right => "ArrowRight".to_string(),
             _ => {
                 // Clean up Debug output: remove "Keycode::" prefix if present
                 debug_str.replace("Keycode::", "")
@@ -122,8 +589,22 @@ impl SharedConfig {
         }
     }
     
-    // Convert string key to Keycode
-    pub fn keycode_from_string(s: &str) -> Keycode {
+    // Convert a typed character back to the physical key that produces it under `layout`.
+    pub fn keycode_from_string(s: &str, layout: KeyboardLayout) -> Keycode {
+        let upper = s.to_uppercase();
+        if upper.chars().count() == 1 {
+            let typed = upper.chars().next().unwrap();
+            for (physical, printed) in layout_letter_overrides(layout) {
+                if *printed == typed {
+                    return Self::physical_keycode_from_string(&physical.to_string());
+                }
+            }
+        }
+        Self::physical_keycode_from_string(&upper)
+    }
+
+    // Convert a physical-key label (already QWERTY-normalized) to Keycode
+    fn physical_keycode_from_string(s: &str) -> Keycode {
         // Try to match the string (case-insensitive)
         let upper = s.to_uppercase();
         match upper.as_str() {
@@ -177,11 +658,24 @@ impl SharedConfig {
             "F11" => Keycode::F11,
             "F12" => Keycode::F12,
             "." | "PERIOD" | "DOT" => Keycode::Dot,
+            "ENTER" => Keycode::Enter,
+            "TAB" => Keycode::Tab,
+            "BACKSPACE" => Keycode::Backspace,
+            "INSERT" => Keycode::Insert,
+            "DELETE" => Keycode::Delete,
+            "HOME" => Keycode::Home,
+            "END" => Keycode::End,
+            "PAGEUP" => Keycode::PageUp,
+            "PAGEDOWN" => Keycode::PageDown,
+            "ARROWUP" => Keycode::Up,
+            "ARROWDOWN" => Keycode::Down,
+            "ARROWLEFT" => Keycode::Left,
+            "ARROWRIGHT" => Keycode::Right,
             _ => {
                 // Try to parse as Debug format (e.g., "Keycode::E" or just "E")
                 if upper.starts_with("KEYCODE::") {
                     let key_name = &upper[9..];
-                    Self::keycode_from_string(key_name)
+                    Self::physical_keycode_from_string(key_name)
                 } else {
                     Keycode::E // Default fallback
                 }
@@ -189,17 +683,67 @@ impl SharedConfig {
         }
     }
     
+    // Parse a chord like "Ctrl+Shift+E" into a Hotkey. Every "+"-separated token
+    // except the last is treated as a modifier; the last token is the base key.
+    pub fn hotkey_from_string(s: &str, layout: KeyboardLayout) -> Hotkey {
+        let mut parts = s.split('+').map(str::trim).filter(|p| !p.is_empty()).peekable();
+        let mut modifiers = Modifiers::empty();
+        let mut base = "";
+
+        while let Some(token) = parts.next() {
+            if parts.peek().is_none() {
+                // Last token is always the base key, even if it happens to look like a modifier name.
+                base = token;
+                break;
+            }
+            match token.to_uppercase().as_str() {
+                "CTRL" | "CONTROL" => modifiers |= Modifiers::CTRL,
+                "SHIFT" => modifiers |= Modifiers::SHIFT,
+                "ALT" => modifiers |= Modifiers::ALT,
+                "META" | "SUPER" | "WIN" => modifiers |= Modifiers::META,
+                _ => {} // Unknown modifier token - ignore rather than panic.
+            }
+        }
+
+        Hotkey::new(Self::keycode_from_string(base, layout), modifiers)
+    }
+
+    // Inverse of hotkey_from_string - human readable so it's safe to hand-edit in a saved RON profile.
+    pub fn hotkey_to_string(hotkey: &Hotkey, layout: KeyboardLayout) -> String {
+        let mut parts = Vec::new();
+        if hotkey.modifiers.contains(Modifiers::CTRL) {
+            parts.push("Ctrl");
+        }
+        if hotkey.modifiers.contains(Modifiers::SHIFT) {
+            parts.push("Shift");
+        }
+        if hotkey.modifiers.contains(Modifiers::ALT) {
+            parts.push("Alt");
+        }
+        if hotkey.modifiers.contains(Modifiers::META) {
+            parts.push("Meta");
+        }
+        let base = Self::keycode_to_string(hotkey.code, layout);
+        if parts.is_empty() {
+            base
+        } else {
+            format!("{}+{}", parts.join("+"), base)
+        }
+    }
+
     // Convert to internal Keybinds structure
     pub fn to_keybinds(&self) -> crate::Keybinds {
+        let layout = self.keyboard_layout;
         crate::Keybinds {
-            melee: Self::keycode_from_string(&self.melee_key),
-            jump: Self::keycode_from_string(&self.jump_key),
+            melee: Self::hotkey_from_string(&self.melee_key, layout),
+            jump: Self::hotkey_from_string(&self.jump_key, layout),
             aim: self.aim_button,
             fire: self.fire_button,
-            emote: Self::keycode_from_string(&self.emote_key),
-            macro_button: self.macro_button,
-            macro_alt: if self.enable_macro_alt { Some(self.macro_alt_button) } else { None },
-            rapid_click: Self::keycode_from_string(&self.rapid_click_key),
+            emote: Self::hotkey_from_string(&self.emote_key, layout),
+            macro_button: self.macro_button.clone(),
+            macro_alt: if self.enable_macro_alt { Some(self.macro_alt_button.clone()) } else { None },
+            rapid_click: Self::hotkey_from_string(&self.rapid_click_key, layout),
+            rapid_click_alt: if self.enable_rapid_click_alt { Some(self.rapid_click_alt_trigger.clone()) } else { None },
         }
     }
     
@@ -216,5 +760,52 @@ impl SharedConfig {
             std::time::Duration::from_millis(self.emote_preparation_delay_manual_ms)
         }
     }
+
+    // Where a named profile lives on disk: "profiles/<name>.ron".
+    pub fn profile_path(name: &str) -> std::path::PathBuf {
+        Path::new(PROFILES_DIR).join(format!("{}.ron", name))
+    }
+
+    // Load a named profile, falling back to defaults if it doesn't exist yet (first run,
+    // or a profile name typed into the GUI that hasn't been saved). #[serde(default)] on
+    // every field added after a profile was last saved means an old file still loads fine.
+    pub fn load_profile(name: &str) -> ConfigResult<Self> {
+        let path = Self::profile_path(name);
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let contents = std::fs::read_to_string(path)?;
+        let config = ron::de::from_str(&contents)?;
+        Ok(config)
+    }
+
+    // Round-trip the whole struct to RON, pretty-printed, creating the profiles directory
+    // on first save.
+    pub fn save_profile(&self, name: &str) -> ConfigResult<()> {
+        std::fs::create_dir_all(PROFILES_DIR)?;
+        let contents = ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default())?;
+        std::fs::write(Self::profile_path(name), contents)?;
+        Ok(())
+    }
+
+    // Every saved profile name (sorted, `.ron` extension stripped), for populating the GUI's
+    // profile dropdown. Empty if no profile has been saved yet.
+    pub fn list_profiles() -> Vec<String> {
+        let mut names: Vec<String> = std::fs::read_dir(PROFILES_DIR)
+            .into_iter()
+            .flatten()
+            .flatten()
+            .filter_map(|entry| {
+                let path = entry.path();
+                if path.extension().and_then(|e| e.to_str()) == Some("ron") {
+                    path.file_stem().and_then(|s| s.to_str()).map(str::to_string)
+                } else {
+                    None
+                }
+            })
+            .collect();
+        names.sort();
+        names
+    }
 }
 
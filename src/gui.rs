@@ -1,12 +1,14 @@
 use eframe::egui;
-use crate::config::SharedConfig;
-use device_query::{DeviceQuery, DeviceState};
+use crate::capture;
+use crate::config::{ActivationTrigger, GamepadTrigger, MouseTrigger, SharedConfig};
+use gilrs::Gilrs;
 use std::sync::{Arc, Mutex};
 use std::thread;
-use std::time::Duration;
 
-#[cfg(target_os = "linux")]
-use evdev::Device;
+// How far a stick/trigger axis has to move during capture before it's recorded as a
+// GamepadTrigger::AxisAbove bind, matching the example deadzone in GamepadTrigger's own
+// doc comment (config.rs).
+const GAMEPAD_AXIS_CAPTURE_THRESHOLD: f32 = 0.6;
 
 #[derive(Clone, Copy, PartialEq, Eq, Debug)]
 enum CaptureTarget {
@@ -19,348 +21,346 @@ enum CaptureTarget {
     FireButton,
     MacroButton,
     MacroAltButton,
+    MacroSlot(usize),
+    RapidClickAltTrigger,
+}
+
+// Accumulates a macro recording while a RecordingSession is active: every capture event
+// that comes through the background thread is timestamped relative to the previous one,
+// producing the same (event, delay) stream recorder::Recording::play expects.
+struct RecordingSession {
+    events: Vec<crate::recorder::Event>,
+    last_time: std::time::Instant,
+}
+
+impl RecordingSession {
+    fn new() -> Self {
+        Self { events: Vec::new(), last_time: std::time::Instant::now() }
+    }
+
+    fn push(&mut self, event: capture::CaptureEvent) {
+        let now = std::time::Instant::now();
+        let delay_since_prev_ms = now.duration_since(self.last_time).as_millis() as u64;
+        self.last_time = now;
+
+        // Scroll notches don't fit the press/release model a Recording replays - there's no
+        // "release" half of a wheel tick - so they're simply not capturable in a recorded
+        // sequence yet, the same way they don't show up as macro_button/macro_alt_button
+        // press/release pairs either.
+        let (kind, target) = match event {
+            capture::CaptureEvent::KeyDown(key) => (crate::recorder::EventKind::Press, crate::recorder::Target::Key(key)),
+            capture::CaptureEvent::KeyUp(key) => (crate::recorder::EventKind::Release, crate::recorder::Target::Key(key)),
+            capture::CaptureEvent::MouseDown(idx) => (crate::recorder::EventKind::Press, crate::recorder::Target::MouseButton(idx)),
+            capture::CaptureEvent::MouseUp(idx) => (crate::recorder::EventKind::Release, crate::recorder::Target::MouseButton(idx)),
+            capture::CaptureEvent::Scroll(_) => return,
+        };
+
+        self.events.push(crate::recorder::Event { kind, target, delay_since_prev_ms });
+    }
+
+    // The Stop Recording click itself is a left-click the global capture thread sees like
+    // any other, and its Press lands in `events` before this session is taken off the Mutex
+    // on the UI thread - there's no reliable way to exclude just that one button's clicks
+    // up front, so instead prune anything left "held" once recording ends. Without this a
+    // replayed recording would leave that button stuck down forever (see chunk0-3's guard
+    // against recording its own record/stop hotkey, the same problem one level removed).
+    fn finish(self) -> Vec<crate::recorder::Event> {
+        // Index of each target's still-unreleased Press, so only that one is dropped and
+        // earlier complete press/release pairs of the same button survive.
+        let mut held = std::collections::HashMap::new();
+        for (i, event) in self.events.iter().enumerate() {
+            match event.kind {
+                crate::recorder::EventKind::Press => { held.insert(event.target, i); }
+                crate::recorder::EventKind::Release => { held.remove(&event.target); }
+            }
+        }
+        let dangling: std::collections::HashSet<usize> = held.into_values().collect();
+        self.events
+            .into_iter()
+            .enumerate()
+            .filter(|(i, _)| !dangling.contains(i))
+            .map(|(_, event)| event)
+            .collect()
+    }
 }
 
 pub struct MacroApp {
     config: std::sync::Arc<std::sync::Mutex<SharedConfig>>,
+    // Pushed to whenever a frame's edits change the config, so the macro engine's input
+    // loop picks up the new settings immediately instead of polling the Mutex itself.
+    command_tx: std::sync::mpsc::Sender<crate::WorkerCommand>,
     capture_target: Arc<Mutex<CaptureTarget>>,
+    recording: Arc<Mutex<Option<RecordingSession>>>,
+    new_macro_name: String,
+    // Profile this config was last loaded from/saved to, the list of other profiles on
+    // disk to populate the dropdown with, the name typed into "Save As New Profile", and
+    // the last RON snapshot written - compared each frame so edits get persisted without
+    // writing the profile file on every single repaint.
+    current_profile: String,
+    profiles: Vec<String>,
+    new_profile_name: String,
+    last_saved_ron: String,
+    // Cheat-sheet overlay toggled by F1 or the "?" button.
+    show_help: bool,
 }
 
 
 impl MacroApp {
-    pub fn new(config: std::sync::Arc<std::sync::Mutex<SharedConfig>>) -> Self {
+    // `capture_rx` is one of the receivers handed out by `capture::spawn_n`, shared with the
+    // macro engine's input thread - so a keybind can be captured here even while the game
+    // window, not this config window, currently has focus.
+    pub fn new(
+        config: std::sync::Arc<std::sync::Mutex<SharedConfig>>,
+        profile: String,
+        capture_rx: std::sync::mpsc::Receiver<capture::CaptureEvent>,
+        command_tx: std::sync::mpsc::Sender<crate::WorkerCommand>,
+    ) -> Self {
         let capture_target = Arc::new(Mutex::new(CaptureTarget::None));
-        
-        #[cfg(target_os = "linux")]
-        {
-            eprintln!("⚠️  Note: Side buttons (button8/button9) may not be detected by device_query on Linux");
-            eprintln!("   We'll try to use evdev as a fallback for side button detection");
-        }
-        
+        let recording = Arc::new(Mutex::new(None));
+
         // Start background thread for key capture
         let config_clone = Arc::clone(&config);
         let capture_target_clone = Arc::clone(&capture_target);
-        
+        let recording_clone = Arc::clone(&recording);
+
         thread::spawn(move || {
             eprintln!("GUI key capture thread started");
-            let device_state = DeviceState::new();
-            let mut last_keys = std::collections::HashSet::new();
-            let mut last_mouse_buttons = Vec::new();
-            let mut capture_started = false;
-            let mut loop_count = 0;
-            
-            #[cfg(target_os = "linux")]
-            let mut evdev_listener: Option<Device> = {
-                // Try to open a mouse device for evdev monitoring
-                use std::fs;
-                use std::path::Path;
-                let input_dir = Path::new("/dev/input");
-                let mut found_device = None;
-                if let Ok(entries) = fs::read_dir(input_dir) {
-                    for entry in entries.flatten() {
-                        let path = entry.path();
-                        if let Some(name) = path.file_name().and_then(|n| n.to_str()) {
-                            if name.starts_with("event") {
-                                match Device::open(&path) {
-                                    Ok(device) => {
-                                        // Check if device has mouse buttons (including side buttons)
-                                        let name_lower = device.name().unwrap_or_default().to_lowercase();
-                                        if name_lower.contains("mouse") || name_lower.contains("pointer") {
-                                            eprintln!("Found potential mouse device: {} ({})", name, device.name().unwrap_or_default());
-                                            found_device = Some(device);
-                                            break;
-                                        }
-                                    }
-                                    Err(e) => {
-                                        // Permission denied is expected if not in input group
-                                        // We'll just skip this device and continue
-                                        if !e.to_string().contains("Permission denied") {
-                                            eprintln!("Warning: Could not open {}: {}", name, e);
-                                        }
-                                    }
-                                }
-                            }
-                        }
-                    }
-                }
-                if found_device.is_none() {
-                    eprintln!("⚠️  Could not open evdev device for side button detection.");
-                    eprintln!("   This is normal if you're not in the 'input' group.");
-                    eprintln!("   Side buttons may still work via device_query, or add yourself to input group:");
-                    eprintln!("   sudo usermod -aG input $USER  (then log out/in)");
+            let events = capture_rx;
+            let mut held_keys: std::collections::HashSet<device_query::Keycode> = std::collections::HashSet::new();
+            // Chord-in-progress: the first non-modifier key pressed while capturing, plus
+            // the modifiers that were held at the moment it went down. The chord commits
+            // once this key is released; modifiers pressed after it went down are not added.
+            let mut chord_terminator: Option<device_query::Keycode> = None;
+            let mut chord_modifiers = crate::config::Modifiers::empty();
+            let mut gilrs = match Gilrs::new() {
+                Ok(g) => Some(g),
+                Err(e) => {
+                    eprintln!("Gamepad capture unavailable ({}), controller bindings can't be set from the GUI", e);
+                    None
                 }
-                found_device
             };
-            
-            loop {
-                thread::sleep(Duration::from_millis(10)); // Poll every 10ms
-                loop_count += 1;
-                
-                // Log every 5 seconds that thread is alive
-                if loop_count % 500 == 0 {
-                    eprintln!("GUI capture thread alive (loop {})", loop_count);
+
+            // Blocks until the backend (see capture.rs) delivers the next key/mouse
+            // transition - no polling interval, no artificial startup delay.
+            for event in events {
+                // Log every event while a recording is in progress, regardless of whatever
+                // single-shot capture_target is (or isn't) active below.
+                if let Some(session) = recording_clone.lock().unwrap().as_mut() {
+                    session.push(event);
                 }
-                
+
                 let current_target = *capture_target_clone.lock().unwrap();
                 if current_target == CaptureTarget::None {
-                    last_keys.clear();
-                    last_mouse_buttons.clear();
-                    capture_started = false;
-                    continue;
-                }
-                
-                // Small delay after starting capture to avoid capturing the click that started it
-                if !capture_started {
-                    capture_started = true;
-                    eprintln!("Starting key capture for: {:?}", current_target);
-                    let keys = device_state.get_keys();
-                    last_keys = keys.iter().cloned().collect();
-                    let mouse = device_state.get_mouse();
-                    last_mouse_buttons = mouse.button_pressed.clone();
-                    eprintln!("Initial state - Keys pressed: {:?}, Mouse buttons: {:?}", 
-                        keys.len(), 
-                        mouse.button_pressed.iter().enumerate().filter(|(_, &p)| p).map(|(i, _)| i).collect::<Vec<_>>()
-                    );
-                    thread::sleep(Duration::from_millis(200)); // Wait 200ms before starting to capture
+                    held_keys.clear();
+                    chord_terminator = None;
+                    chord_modifiers = crate::config::Modifiers::empty();
                     continue;
                 }
-                
-                let keys = device_state.get_keys();
-                let mouse = device_state.get_mouse();
-                
-                // On Linux, also check evdev for side buttons
-                #[cfg(target_os = "linux")]
-                {
-                    if let Some(ref mut evdev_device) = evdev_listener {
-                        if matches!(current_target, CaptureTarget::AimButton | CaptureTarget::FireButton | CaptureTarget::MacroButton | CaptureTarget::MacroAltButton) {
-                            // Try to read events from evdev (non-blocking)
-                            match evdev_device.fetch_events() {
-                                Ok(events) => {
-                                    for event in events {
-                                        // Check if this is a key event
-                                        if event.event_type().0 == 1 { // EV_KEY = 1
-                                            let code = event.code();
-                                            // BTN_SIDE = 0x113 (275), BTN_EXTRA = 0x114 (276)
-                                            // But we need to check the actual key code
-                                            if code == 0x113 || code == 275 {
-                                                eprintln!("✅ Side button 1 (BTN_SIDE, code {}) detected via evdev!", code);
-                                                let mut config = config_clone.lock().unwrap();
-                                                match current_target {
-                                                    CaptureTarget::AimButton => config.aim_button = 8,
-                                                    CaptureTarget::FireButton => config.fire_button = 8,
-                                                    CaptureTarget::MacroButton => config.macro_button = 8,
-                                                    CaptureTarget::MacroAltButton => config.macro_alt_button = 8,
-                                                    _ => {}
-                                                }
-                                                *capture_target_clone.lock().unwrap() = CaptureTarget::None;
-                                                capture_started = false;
-                                                continue;
-                                            } else if code == 0x114 || code == 276 {
-                                                eprintln!("✅ Side button 2 (BTN_EXTRA, code {}) detected via evdev!", code);
-                                                let mut config = config_clone.lock().unwrap();
-                                                match current_target {
-                                                    CaptureTarget::AimButton => config.aim_button = 9,
-                                                    CaptureTarget::FireButton => config.fire_button = 9,
-                                                    CaptureTarget::MacroButton => config.macro_button = 9,
-                                                    CaptureTarget::MacroAltButton => config.macro_alt_button = 9,
-                                                    _ => {}
-                                                }
-                                                *capture_target_clone.lock().unwrap() = CaptureTarget::None;
-                                                capture_started = false;
-                                                continue;
-                                            }
-                                        }
-                                    }
+
+                // Drain gamepad events here, since the event channel above only ever carries
+                // key/mouse events. This runs only when such an event arrives, so a pad press
+                // during capture is picked up on the next key/mouse/scroll event.
+                if let Some(ref mut pad_state) = gilrs {
+                    if matches!(current_target, CaptureTarget::MacroButton | CaptureTarget::MacroAltButton | CaptureTarget::MacroSlot(_) | CaptureTarget::RapidClickAltTrigger) {
+                        while let Some(pad_event) = pad_state.next_event() {
+                            let trigger = match pad_event.event {
+                                gilrs::EventType::ButtonPressed(button, _) => {
+                                    let tag = format!("{:?}", button);
+                                    eprintln!("✅ Captured gamepad button: {}", tag);
+                                    GamepadTrigger::Button(tag)
                                 }
-                                Err(_) => {
-                                    // Device might have been disconnected or no events available
-                                    // This is normal, just continue
+                                // A held trigger/stick rests near 0.0 and reports every tiny
+                                // jitter as its own AxisChanged - only capture once a push
+                                // crosses GAMEPAD_AXIS_CAPTURE_THRESHOLD, same deadzone
+                                // gamepad_trigger_active checks against at runtime.
+                                gilrs::EventType::AxisChanged(axis, value, _)
+                                    if value.abs() >= GAMEPAD_AXIS_CAPTURE_THRESHOLD =>
+                                {
+                                    let tag = format!("{:?}", axis);
+                                    eprintln!("✅ Captured gamepad axis: {} > {}", tag, GAMEPAD_AXIS_CAPTURE_THRESHOLD);
+                                    GamepadTrigger::AxisAbove(tag, GAMEPAD_AXIS_CAPTURE_THRESHOLD)
                                 }
+                                _ => continue,
+                            };
+                            let trigger = ActivationTrigger::Gamepad(trigger);
+                            let mut config = config_clone.lock().unwrap();
+                            match current_target {
+                                CaptureTarget::MacroButton => config.macro_button.trigger = trigger,
+                                CaptureTarget::MacroAltButton => config.macro_alt_button.trigger = trigger,
+                                CaptureTarget::MacroSlot(slot) => {
+                                    if let Some(m) = config.macros.get_mut(slot) {
+                                        m.trigger.trigger = trigger;
+                                    }
+                                }
+                                CaptureTarget::RapidClickAltTrigger => config.rapid_click_alt_trigger.trigger = trigger,
+                                _ => {}
                             }
+                            *capture_target_clone.lock().unwrap() = CaptureTarget::None;
                         }
                     }
                 }
-                
-                // Log mouse button state when capturing mouse buttons (but only when state changes to avoid spam)
-                if matches!(current_target, CaptureTarget::AimButton | CaptureTarget::FireButton | CaptureTarget::MacroButton | CaptureTarget::MacroAltButton) {
-                    let current_pressed: Vec<usize> = mouse.button_pressed.iter().enumerate()
-                        .filter(|(_, &p)| p)
-                        .map(|(i, _)| i)
-                        .collect();
-                    let last_pressed: Vec<usize> = last_mouse_buttons.iter().enumerate()
-                        .filter(|(_, p)| **p)
-                        .map(|(i, _)| i)
-                        .collect();
-                    
-                    // Only log when button state changes
-                    if current_pressed != last_pressed {
-                        eprintln!("🔍 Mouse button state changed - Pressed indices: {:?}, Array length: {}, Full array: {:?}", 
-                            current_pressed, 
-                            mouse.button_pressed.len(),
-                            mouse.button_pressed
-                        );
-                        eprintln!("   NOTE: If side buttons aren't showing, device_query may not support them on Linux");
-                        eprintln!("   Side buttons might need to be detected via evdev or xdotool instead");
-                    }
-                }
-                
-                // Debug: log when keys change
-                if keys.len() != last_keys.len() || !keys.iter().all(|k| last_keys.contains(k)) {
-                    let new_keys: Vec<_> = keys.iter().filter(|k| !last_keys.contains(k)).collect();
-                    if !new_keys.is_empty() {
-                        eprintln!("New keys detected: {:?}", new_keys);
+
+                match event {
+                    capture::CaptureEvent::KeyDown(key) => {
+                        held_keys.insert(key);
+
+                        if key == device_query::Keycode::Escape {
+                            eprintln!("Escape pressed, canceling capture");
+                            *capture_target_clone.lock().unwrap() = CaptureTarget::None;
+                            continue;
+                        }
+
+                        // Only keyboard keybind targets build a chord out of key presses.
+                        if matches!(current_target, CaptureTarget::MeleeKey | CaptureTarget::JumpKey | CaptureTarget::EmoteKey | CaptureTarget::RapidClickKey)
+                            && chord_terminator.is_none()
+                        {
+                            let is_modifier = matches!(key,
+                                device_query::Keycode::LControl | device_query::Keycode::RControl |
+                                device_query::Keycode::LShift | device_query::Keycode::RShift |
+                                device_query::Keycode::LAlt | device_query::Keycode::RAlt);
+                            if !is_modifier {
+                                chord_terminator = Some(key);
+                                chord_modifiers = crate::config::held_modifiers(&held_keys.iter().cloned().collect::<Vec<_>>());
+                            }
+                        }
                     }
-                }
-                
-                // Debug: log when mouse buttons change
-                let current_pressed: Vec<usize> = mouse.button_pressed.iter().enumerate()
-                    .filter(|(_, &p)| p)
-                    .map(|(i, _)| i)
-                    .collect();
-                let last_pressed: Vec<usize> = last_mouse_buttons.iter().enumerate()
-                    .filter(|(_, p)| **p)
-                    .map(|(i, _)| i)
-                    .collect();
-                if current_pressed != last_pressed {
-                    let new_buttons: Vec<usize> = current_pressed.iter()
-                        .filter(|&&i| !last_pressed.contains(&i))
-                        .copied()
-                        .collect();
-                    if !new_buttons.is_empty() {
-                        eprintln!("New mouse buttons detected: {:?} (full array: {:?})", new_buttons, mouse.button_pressed);
+                    capture::CaptureEvent::KeyUp(key) => {
+                        held_keys.remove(&key);
+
+                        // The chord commits once its terminator key is released, so for
+                        // "Ctrl+Shift+X" the modifiers must already be down when X is pressed.
+                        if chord_terminator == Some(key) {
+                            let layout = config_clone.lock().unwrap().keyboard_layout;
+                            let hotkey = crate::config::Hotkey::new(key, chord_modifiers);
+                            let key_name = SharedConfig::hotkey_to_string(&hotkey, layout);
+                            eprintln!("Captured chord: {}", key_name);
+
+                            let mut config = config_clone.lock().unwrap();
+                            match current_target {
+                                CaptureTarget::MeleeKey => config.melee_key = key_name.clone(),
+                                CaptureTarget::JumpKey => config.jump_key = key_name.clone(),
+                                CaptureTarget::EmoteKey => config.emote_key = key_name.clone(),
+                                CaptureTarget::RapidClickKey => config.rapid_click_key = key_name.clone(),
+                                _ => {}
+                            }
+                            eprintln!("Updated {:?} to: {}", current_target, key_name);
+
+                            *capture_target_clone.lock().unwrap() = CaptureTarget::None;
+                            chord_terminator = None;
+                            chord_modifiers = crate::config::Modifiers::empty();
+                        }
                     }
-                }
-                
-                // Check for Escape to cancel capture
-                use device_query::Keycode;
-                if keys.contains(&Keycode::Escape) && !last_keys.contains(&Keycode::Escape) {
-                    eprintln!("Escape pressed, canceling capture");
-                    *capture_target_clone.lock().unwrap() = CaptureTarget::None;
-                    capture_started = false;
-                    continue;
-                }
-                
-                // Check for keyboard keys (only for keyboard keybind targets)
-                match current_target {
-                    CaptureTarget::MeleeKey | CaptureTarget::JumpKey | CaptureTarget::EmoteKey | CaptureTarget::RapidClickKey => {
-                        // Find newly pressed keys (keys that are pressed now but weren't before)
-                        for key in &keys {
-                            if !last_keys.contains(key) {
-                                // Ignore modifier keys and function keys that might be used by the system
-                                use device_query::Keycode;
-                                match key {
-                                    Keycode::LControl | Keycode::RControl |
-                                    Keycode::LShift | Keycode::RShift |
-                                    Keycode::LAlt | Keycode::RAlt => {
-                                        // Skip modifier keys, continue to next key
-                                        continue;
-                                    }
-                                    _ => {
-                                        // Found a newly pressed key
-                                        let key_name = SharedConfig::keycode_to_string(*key);
-                                        eprintln!("Captured key: {} -> {}", format!("{:?}", key), key_name);
-                                        
-                                        let mut config = config_clone.lock().unwrap();
-                                        match current_target {
-                                            CaptureTarget::MeleeKey => config.melee_key = key_name.clone(),
-                                            CaptureTarget::JumpKey => config.jump_key = key_name.clone(),
-                                            CaptureTarget::EmoteKey => config.emote_key = key_name.clone(),
-                                            CaptureTarget::RapidClickKey => config.rapid_click_key = key_name.clone(),
-                                            _ => {}
-                                        }
-                                        eprintln!("Updated {} to: {}", 
-                                            match current_target {
-                                                CaptureTarget::MeleeKey => "melee_key",
-                                                CaptureTarget::JumpKey => "jump_key",
-                                                CaptureTarget::EmoteKey => "emote_key",
-                                                CaptureTarget::RapidClickKey => "rapid_click_key",
-                                                _ => "unknown",
-                                            },
-                                            key_name
-                                        );
-                                        *capture_target_clone.lock().unwrap() = CaptureTarget::None;
-                                        capture_started = false;
-                                        break;
+                    capture::CaptureEvent::MouseDown(idx) => {
+                        if matches!(current_target, CaptureTarget::AimButton | CaptureTarget::FireButton | CaptureTarget::MacroButton | CaptureTarget::MacroAltButton | CaptureTarget::MacroSlot(_) | CaptureTarget::RapidClickAltTrigger) {
+                            eprintln!("✅ Captured mouse button at index: {}", idx);
+                            let mut config = config_clone.lock().unwrap();
+                            match current_target {
+                                CaptureTarget::AimButton => {
+                                    config.aim_button = idx;
+                                    eprintln!("Updated aim_button to: {}", idx);
+                                }
+                                CaptureTarget::FireButton => {
+                                    config.fire_button = idx;
+                                    eprintln!("Updated fire_button to: {}", idx);
+                                }
+                                CaptureTarget::MacroButton => {
+                                    config.macro_button.trigger = ActivationTrigger::Mouse(MouseTrigger::Button(idx));
+                                    eprintln!("Updated macro_button to: {}", idx);
+                                }
+                                CaptureTarget::MacroAltButton => {
+                                    config.macro_alt_button.trigger = ActivationTrigger::Mouse(MouseTrigger::Button(idx));
+                                    eprintln!("Updated macro_alt_button to: {}", idx);
+                                }
+                                CaptureTarget::MacroSlot(slot) => {
+                                    if let Some(m) = config.macros.get_mut(slot) {
+                                        m.trigger.trigger = ActivationTrigger::Mouse(MouseTrigger::Button(idx));
+                                        eprintln!("Updated macro slot {} trigger to button {}", slot, idx);
                                     }
                                 }
+                                CaptureTarget::RapidClickAltTrigger => {
+                                    config.rapid_click_alt_trigger.trigger = ActivationTrigger::Mouse(MouseTrigger::Button(idx));
+                                    eprintln!("Updated rapid_click_alt_trigger to button {}", idx);
+                                }
+                                _ => {}
                             }
+                            *capture_target_clone.lock().unwrap() = CaptureTarget::None;
                         }
                     }
-                    _ => {}
-                }
-                
-                // Check for mouse buttons (only for mouse button targets)
-                match current_target {
-                    CaptureTarget::AimButton | CaptureTarget::FireButton | CaptureTarget::MacroButton | CaptureTarget::MacroAltButton => {
-                        // Find newly pressed mouse buttons
-                        // Check all button indices - side buttons can be at various indices
-                        eprintln!("Checking mouse buttons - Current: {:?}, Last: {:?}", 
-                            mouse.button_pressed.iter().enumerate()
-                                .filter(|(_, &p)| p)
-                                .map(|(i, _)| i)
-                                .collect::<Vec<_>>(),
-                            last_mouse_buttons.iter().enumerate()
-                                .filter(|(_, p)| **p)
-                                .map(|(i, _)| i)
-                                .collect::<Vec<_>>()
-                        );
-                        
-                        // Check all possible button indices
-                        // device_query's button_pressed is a Vec<bool> where indices might not match pynput exactly
-                        // pynput uses: button8 (index 8) and button9 (index 9) for side buttons
-                        // But device_query might use different indices, so we check all
-                        // Also check beyond the array length in case device_query uses sparse arrays
-                        let max_check = mouse.button_pressed.len().max(10); // Check at least up to index 9
-                        for idx in 0..max_check {
-                            let pressed = mouse.button_pressed.get(idx).copied().unwrap_or(false);
-                            if pressed {
-                                // Check if this button was just pressed (wasn't pressed before)
-                                let was_pressed = last_mouse_buttons.get(idx).copied().unwrap_or(false);
-                                if !was_pressed {
-                                    eprintln!("✅ Captured mouse button at index: {} (button_pressed.len() = {})", 
-                                        idx, mouse.button_pressed.len());
-                                    eprintln!("   Full button_pressed array: {:?}", mouse.button_pressed);
-                                    
-                                    let mut config = config_clone.lock().unwrap();
-                                    match current_target {
-                                        CaptureTarget::AimButton => {
-                                            config.aim_button = idx;
-                                            eprintln!("Updated aim_button to: {}", idx);
-                                        }
-                                        CaptureTarget::FireButton => {
-                                            config.fire_button = idx;
-                                            eprintln!("Updated fire_button to: {}", idx);
-                                        }
-                                        CaptureTarget::MacroButton => {
-                                            config.macro_button = idx;
-                                            eprintln!("Updated macro_button to: {} (this should be 8 for side button 1)", idx);
-                                        }
-                                        CaptureTarget::MacroAltButton => {
-                                            config.macro_alt_button = idx;
-                                            eprintln!("Updated macro_alt_button to: {} (this should be 9 for side button 2)", idx);
-                                        }
-                                        _ => {}
+                    capture::CaptureEvent::MouseUp(_) => {}
+                    capture::CaptureEvent::Scroll(direction) => {
+                        if matches!(current_target, CaptureTarget::MacroButton | CaptureTarget::MacroAltButton | CaptureTarget::MacroSlot(_) | CaptureTarget::RapidClickAltTrigger) {
+                            let mouse_trigger = match direction {
+                                capture::ScrollDirection::Up => MouseTrigger::ScrollUp,
+                                capture::ScrollDirection::Down => MouseTrigger::ScrollDown,
+                                capture::ScrollDirection::Left => MouseTrigger::ScrollLeft,
+                                capture::ScrollDirection::Right => MouseTrigger::ScrollRight,
+                            };
+                            eprintln!("✅ Captured scroll: {:?}", direction);
+                            let trigger = ActivationTrigger::Mouse(mouse_trigger);
+                            let mut config = config_clone.lock().unwrap();
+                            match current_target {
+                                CaptureTarget::MacroButton => config.macro_button.trigger = trigger,
+                                CaptureTarget::MacroAltButton => config.macro_alt_button.trigger = trigger,
+                                CaptureTarget::MacroSlot(slot) => {
+                                    if let Some(m) = config.macros.get_mut(slot) {
+                                        m.trigger.trigger = trigger;
                                     }
-                                    *capture_target_clone.lock().unwrap() = CaptureTarget::None;
-                                    capture_started = false;
-                                    break;
                                 }
+                                CaptureTarget::RapidClickAltTrigger => config.rapid_click_alt_trigger.trigger = trigger,
+                                _ => {}
                             }
+                            *capture_target_clone.lock().unwrap() = CaptureTarget::None;
                         }
                     }
-                    _ => {}
                 }
-                
-                // Update last state
-                last_keys = keys.iter().cloned().collect();
-                last_mouse_buttons = mouse.button_pressed.clone();
             }
         });
         
+        let last_saved_ron = ron::ser::to_string(&*config.lock().unwrap()).unwrap_or_default();
+        let mut profiles = SharedConfig::list_profiles();
+        if !profiles.contains(&profile) {
+            profiles.push(profile.clone());
+            profiles.sort();
+        }
+
         Self {
             config,
+            command_tx,
             capture_target,
+            recording,
+            new_macro_name: String::new(),
+            current_profile: profile,
+            profiles,
+            new_profile_name: String::new(),
+            last_saved_ron,
+            show_help: false,
+        }
+    }
+
+    // Declarative (action_label, binding) pairs for the cheat-sheet overlay - melee/jump/
+    // emote/rapid-click keys, the aim/fire/macro mouse buttons, and every named macro's
+    // trigger, so a new action shows up here automatically instead of needing its own entry
+    // wired up by hand.
+    fn keybind_summary(config: &SharedConfig) -> Vec<(String, String)> {
+        let mut rows = vec![
+            ("Melee Key".to_string(), config.melee_key.clone()),
+            ("Jump Key".to_string(), config.jump_key.clone()),
+            ("Emote Key".to_string(), config.emote_key.clone()),
+            ("Rapid Click Key".to_string(), config.rapid_click_key.clone()),
+            ("Aim Button".to_string(), format!("Mouse {}", config.aim_button)),
+            ("Fire Button".to_string(), format!("Mouse {}", config.fire_button)),
+            ("Macro Button".to_string(), config.macro_button.to_string()),
+        ];
+        if config.enable_macro_alt {
+            rows.push(("Alt Macro Button".to_string(), config.macro_alt_button.to_string()));
+        }
+        if config.enable_rapid_click_alt {
+            rows.push(("Alt Rapid-Click Trigger".to_string(), config.rapid_click_alt_trigger.to_string()));
+        }
+        for named_macro in &config.macros {
+            rows.push((format!("Macro: {}", named_macro.name), named_macro.trigger.to_string()));
         }
+        rows
     }
     
     fn keybind_button(ui: &mut egui::Ui, label: &str, value: &mut String, capture_target: CaptureTarget, capture_target_arc: &Arc<Mutex<CaptureTarget>>) {
@@ -424,99 +424,304 @@ impl MacroApp {
             ui.add(egui::Slider::new(value, 1..=10));
         });
     }
+
+    // Like mouse_button_slider, but for an ActivationTrigger field, which can be bound to
+    // a mouse button/scroll/drag, a gamepad button, or a gamepad axis crossing
+    // GAMEPAD_AXIS_CAPTURE_THRESHOLD - whichever the capture thread sees fire first while
+    // listening. Mouse Button()/Drag() triggers get the same numeric slider as
+    // mouse_button_slider; ScrollUp/ScrollDown and gamepad bindings just display their name
+    // (the axis threshold itself isn't adjustable from the GUI yet - rebinding re-captures
+    // it at the fixed threshold).
+    fn activation_trigger_control(ui: &mut egui::Ui, label: &str, value: &mut ActivationTrigger, capture_target: CaptureTarget, capture_target_arc: &Arc<Mutex<CaptureTarget>>) {
+        ui.horizontal(|ui| {
+            ui.label(format!("{}:", label));
+
+            let current_capture = *capture_target_arc.lock().unwrap();
+            let button_text = if current_capture == capture_target {
+                "Press button..."
+            } else {
+                "Set"
+            };
+
+            let button_color = if current_capture == capture_target {
+                egui::Color32::from_rgb(255, 200, 0)
+            } else {
+                egui::Color32::from_rgb(100, 100, 100)
+            };
+
+            let response = ui.add_sized(
+                [100.0, 20.0],
+                egui::Button::new(button_text).fill(button_color)
+            );
+
+            if response.clicked() {
+                *capture_target_arc.lock().unwrap() = capture_target;
+            }
+
+            match value {
+                ActivationTrigger::Mouse(MouseTrigger::Button(idx)) | ActivationTrigger::Mouse(MouseTrigger::Drag(idx)) => {
+                    ui.add(egui::Slider::new(idx, 1..=10));
+                }
+                ActivationTrigger::Mouse(MouseTrigger::ScrollUp) => {
+                    ui.label("Scroll Up");
+                }
+                ActivationTrigger::Mouse(MouseTrigger::ScrollDown) => {
+                    ui.label("Scroll Down");
+                }
+                ActivationTrigger::Mouse(MouseTrigger::ScrollLeft) => {
+                    ui.label("Scroll Left");
+                }
+                ActivationTrigger::Mouse(MouseTrigger::ScrollRight) => {
+                    ui.label("Scroll Right");
+                }
+                ActivationTrigger::Gamepad(trigger) => {
+                    ui.label(trigger.to_string());
+                }
+            }
+        });
+    }
+}
+
+// Convert an egui::Key into the same key-name vocabulary SharedConfig's
+// keycode_from_string/keycode_to_string use - covers everything the old ctx.input() match
+// named plus the navigation keys it dropped (Home/End/PageUp/PageDown/Insert/Delete).
+// Escape isn't named here since raw_input_hook handles it as the dedicated cancel key.
+fn egui_key_name(key: egui::Key) -> Option<String> {
+    Some(match key {
+        egui::Key::Escape => return None,
+        egui::Key::Space => "Space".to_string(),
+        egui::Key::Enter => "Enter".to_string(),
+        egui::Key::Tab => "Tab".to_string(),
+        egui::Key::Backspace => "Backspace".to_string(),
+        egui::Key::Delete => "Delete".to_string(),
+        egui::Key::Insert => "Insert".to_string(),
+        egui::Key::Home => "Home".to_string(),
+        egui::Key::End => "End".to_string(),
+        egui::Key::PageUp => "PageUp".to_string(),
+        egui::Key::PageDown => "PageDown".to_string(),
+        egui::Key::ArrowUp => "ArrowUp".to_string(),
+        egui::Key::ArrowDown => "ArrowDown".to_string(),
+        egui::Key::ArrowLeft => "ArrowLeft".to_string(),
+        egui::Key::ArrowRight => "ArrowRight".to_string(),
+        egui::Key::F1 => "F1".to_string(),
+        egui::Key::F2 => "F2".to_string(),
+        egui::Key::F3 => "F3".to_string(),
+        egui::Key::F4 => "F4".to_string(),
+        egui::Key::F5 => "F5".to_string(),
+        egui::Key::F6 => "F6".to_string(),
+        egui::Key::F7 => "F7".to_string(),
+        egui::Key::F8 => "F8".to_string(),
+        egui::Key::F9 => "F9".to_string(),
+        egui::Key::F10 => "F10".to_string(),
+        egui::Key::F11 => "F11".to_string(),
+        egui::Key::F12 => "F12".to_string(),
+        _ => {
+            // For letter/digit keys, try to get the character from the debug name.
+            let key_str = format!("{:?}", key);
+            if let Some(letter) = key_str.strip_prefix("Key") {
+                letter.to_string()
+            } else if let Some(digit) = key_str.strip_prefix("Num") {
+                digit.to_string()
+            } else {
+                key_str
+            }
+        }
+    })
+}
+
+// Build the same "Ctrl+Shift+E" chord notation SharedConfig::hotkey_to_string/
+// hotkey_from_string already use, so combos like Ctrl+Space that avoid clashing with
+// in-game keys round-trip through the runtime matcher without any new parsing.
+fn build_modifier_chord(modifiers: &egui::Modifiers, key_name: &str) -> String {
+    let mut parts = Vec::new();
+    if modifiers.ctrl {
+        parts.push("Ctrl");
+    }
+    if modifiers.shift {
+        parts.push("Shift");
+    }
+    if modifiers.alt {
+        parts.push("Alt");
+    }
+    if modifiers.mac_cmd {
+        parts.push("Meta");
+    }
+    parts.push(key_name);
+    parts.join("+")
 }
 
 impl eframe::App for MacroApp {
-    fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
-        // Also check egui's input for keyboard keys (works when window has focus)
+    // Intercept the raw input stream before egui turns it into widget interactions - the
+    // single authoritative capture point for the keyboard-binding targets. Swallowing the
+    // consumed event here (instead of merely reading it in `update`) stops the captured
+    // keystroke/click from also landing on whatever widget is underneath the "listening"
+    // overlay, and lets us grab keys like Home/End/PageUp that egui's `ctx.input()` events
+    // carry but the old string-matching never named.
+    fn raw_input_hook(&mut self, _ctx: &egui::Context, raw_input: &mut egui::RawInput) {
         let current_capture = *self.capture_target.lock().unwrap();
-        
-        // Use egui input for keyboard keys when capturing (works when window has focus)
-        match current_capture {
-            CaptureTarget::MeleeKey | CaptureTarget::JumpKey | CaptureTarget::EmoteKey | CaptureTarget::RapidClickKey => {
-                // Check for key presses via egui events
-                let mut captured_key: Option<String> = None;
-                ctx.input(|i| {
-                    for event in &i.events {
-                        if let egui::Event::Key { key, pressed: true, modifiers, .. } = event {
-                            // Skip if modifier keys are held (unless it's just the key itself)
-                            if modifiers.ctrl || modifiers.alt || modifiers.shift || modifiers.mac_cmd {
-                                continue;
-                            }
-                            
-                            // Convert egui::Key to string
-                            let key_name = match key {
-                                egui::Key::Space => "Space".to_string(),
-                                egui::Key::Enter => "Enter".to_string(),
-                                egui::Key::Tab => "Tab".to_string(),
-                                egui::Key::Backspace => "Backspace".to_string(),
-                                egui::Key::Escape => {
-                                    // Cancel capture on Escape
-                                    *self.capture_target.lock().unwrap() = CaptureTarget::None;
-                                    return;
-                                }
-                                egui::Key::ArrowUp => "ArrowUp".to_string(),
-                                egui::Key::ArrowDown => "ArrowDown".to_string(),
-                                egui::Key::ArrowLeft => "ArrowLeft".to_string(),
-                                egui::Key::ArrowRight => "ArrowRight".to_string(),
-                                egui::Key::F1 => "F1".to_string(),
-                                egui::Key::F2 => "F2".to_string(),
-                                egui::Key::F3 => "F3".to_string(),
-                                egui::Key::F4 => "F4".to_string(),
-                                egui::Key::F5 => "F5".to_string(),
-                                egui::Key::F6 => "F6".to_string(),
-                                egui::Key::F7 => "F7".to_string(),
-                                egui::Key::F8 => "F8".to_string(),
-                                egui::Key::F9 => "F9".to_string(),
-                                egui::Key::F10 => "F10".to_string(),
-                                egui::Key::F11 => "F11".to_string(),
-                                egui::Key::F12 => "F12".to_string(),
-                                _ => {
-                                    // For letter keys, try to get the character from text events
-                                    let key_str = format!("{:?}", key);
-                                    if let Some(letter) = key_str.strip_prefix("Key") {
-                                        letter.to_string()
-                                    } else {
-                                        key_str
-                                    }
-                                }
-                            };
-                            
-                            eprintln!("Captured key via egui: {:?} -> {}", key, key_name);
-                            captured_key = Some(key_name);
-                            break;
-                        }
-                    }
-                });
-                
-                if let Some(key_name) = captured_key {
-                    let mut config = self.config.lock().unwrap();
-                    match current_capture {
-                        CaptureTarget::MeleeKey => config.melee_key = key_name.clone(),
-                        CaptureTarget::JumpKey => config.jump_key = key_name.clone(),
-                        CaptureTarget::EmoteKey => config.emote_key = key_name.clone(),
-                        CaptureTarget::RapidClickKey => config.rapid_click_key = key_name.clone(),
-                        _ => {}
+        if current_capture == CaptureTarget::None {
+            return;
+        }
+
+        let is_keyboard_target = matches!(
+            current_capture,
+            CaptureTarget::MeleeKey | CaptureTarget::JumpKey | CaptureTarget::EmoteKey | CaptureTarget::RapidClickKey
+        );
+
+        let mut captured_chord: Option<String> = None;
+        let mut cancel = false;
+
+        raw_input.events.retain(|event| match event {
+            egui::Event::Key { key, pressed: true, modifiers, .. } => {
+                if *key == egui::Key::Escape {
+                    cancel = true;
+                    return false; // swallow - never reaches widgets
+                }
+                if is_keyboard_target && captured_chord.is_none() {
+                    if let Some(key_name) = egui_key_name(*key) {
+                        captured_chord = Some(build_modifier_chord(modifiers, &key_name));
                     }
-                    eprintln!("Updated config via egui input");
-                    *self.capture_target.lock().unwrap() = CaptureTarget::None;
+                    return false; // swallow the keystroke being captured
                 }
+                true
+            }
+            // Not a keyboard target, so a mouse binding is being listened for by the
+            // background capture thread (see capture.rs) - swallow the click here too so it
+            // doesn't also register as a UI click on whatever's under the cursor.
+            egui::Event::PointerButton { pressed: true, .. } if !is_keyboard_target => false,
+            _ => true,
+        });
+
+        if cancel {
+            eprintln!("Escape pressed, canceling capture");
+            *self.capture_target.lock().unwrap() = CaptureTarget::None;
+            return;
+        }
+
+        if let Some(chord) = captured_chord {
+            eprintln!("Captured key via raw_input_hook: {}", chord);
+            let mut config = self.config.lock().unwrap();
+            match current_capture {
+                CaptureTarget::MeleeKey => config.melee_key = chord,
+                CaptureTarget::JumpKey => config.jump_key = chord,
+                CaptureTarget::EmoteKey => config.emote_key = chord,
+                CaptureTarget::RapidClickKey => config.rapid_click_key = chord,
+                _ => {}
             }
-            _ => {}
+            *self.capture_target.lock().unwrap() = CaptureTarget::None;
         }
-        
+    }
+
+    fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+        let current_capture = *self.capture_target.lock().unwrap();
+
         // Request frequent repaints if we're capturing to show visual feedback
         if current_capture != CaptureTarget::None {
             ctx.request_repaint_after(std::time::Duration::from_millis(50)); // Update UI every 50ms
         }
-        
+
+        if ctx.input(|i| i.key_pressed(egui::Key::F1)) {
+            self.show_help = !self.show_help;
+        }
+
+        if self.show_help {
+            let config_snapshot = self.config.lock().unwrap().clone();
+            let rows = Self::keybind_summary(&config_snapshot);
+
+            // A binding used by more than one action is a real conflict - whichever fires
+            // first on a shared input silently eats the other's press - so call it out
+            // instead of making the user cross-reference every collapsing section by hand.
+            let mut counts: std::collections::HashMap<&str, usize> = std::collections::HashMap::new();
+            for (_, binding) in &rows {
+                *counts.entry(binding.as_str()).or_insert(0) += 1;
+            }
+
+            egui::Window::new("⌨ Keybind Cheat Sheet")
+                .open(&mut self.show_help)
+                .resizable(true)
+                .show(ctx, |ui| {
+                    ui.label("Press F1 or the ? button to close. Conflicting bindings are highlighted in red.");
+                    ui.separator();
+                    egui::ScrollArea::vertical().show(ui, |ui| {
+                        egui::Grid::new("keybind_cheat_sheet_grid")
+                            .num_columns(2)
+                            .striped(true)
+                            .show(ui, |ui| {
+                                for (label, binding) in &rows {
+                                    ui.label(label);
+                                    if counts.get(binding.as_str()).copied().unwrap_or(0) > 1 {
+                                        ui.colored_label(egui::Color32::RED, format!("{} (conflict!)", binding));
+                                    } else {
+                                        ui.label(binding);
+                                    }
+                                    ui.end_row();
+                                }
+                            });
+                    });
+                });
+        }
+
         egui::CentralPanel::default().show(ctx, |ui| {
-            ui.heading("Exodia Contagion Macro - Configuration");
-            
+            ui.horizontal(|ui| {
+                ui.heading("Exodia Contagion Macro - Configuration");
+                if ui.button("❓").on_hover_text("Keybind cheat sheet (F1)").clicked() {
+                    self.show_help = !self.show_help;
+                }
+            });
+
             ui.separator();
             
             let mut config = self.config.lock().unwrap();
-            
+
+            // Named profiles - switch whole macro setups (timings + keybinds) without
+            // re-entering every value, the same way these TUI apps ship a config.ron per preset.
+            ui.collapsing("📁 Profile", |ui| {
+                ui.horizontal(|ui| {
+                    ui.label("Active profile:");
+                    let mut switch_to: Option<String> = None;
+                    egui::ComboBox::from_id_source("profile_select")
+                        .selected_text(self.current_profile.clone())
+                        .show_ui(ui, |ui| {
+                            for name in &self.profiles {
+                                if ui.selectable_label(*name == self.current_profile, name).clicked() {
+                                    switch_to = Some(name.clone());
+                                }
+                            }
+                        });
+                    if let Some(name) = switch_to {
+                        match SharedConfig::load_profile(&name) {
+                            Ok(loaded) => {
+                                *config = loaded;
+                                self.current_profile = name;
+                                self.last_saved_ron = ron::ser::to_string(&*config).unwrap_or_default();
+                            }
+                            Err(e) => eprintln!("Failed to load profile '{}': {}", name, e),
+                        }
+                    }
+                });
+
+                ui.horizontal(|ui| {
+                    ui.label("Save as new profile:");
+                    ui.text_edit_singleline(&mut self.new_profile_name);
+                    let can_save = !self.new_profile_name.trim().is_empty();
+                    if ui.add_enabled(can_save, egui::Button::new("💾 Save As")).clicked() {
+                        let name = std::mem::take(&mut self.new_profile_name);
+                        if let Err(e) = config.save_profile(&name) {
+                            eprintln!("Failed to save profile '{}': {}", name, e);
+                        } else {
+                            if !self.profiles.contains(&name) {
+                                self.profiles.push(name.clone());
+                                self.profiles.sort();
+                            }
+                            self.current_profile = name;
+                            self.last_saved_ron = ron::ser::to_string(&*config).unwrap_or_default();
+                        }
+                    }
+                });
+            });
+
+            ui.separator();
+
             // Timing Configuration
             ui.collapsing("⏱️ Timing Settings", |ui| {
                 ui.add(egui::Slider::new(&mut config.fps, 30.0..=300.0).text("Game FPS"));
@@ -552,20 +757,73 @@ impl eframe::App for MacroApp {
                 // Mouse buttons
                 Self::mouse_button_slider(ui, "Aim Button", &mut config.aim_button, CaptureTarget::AimButton, &self.capture_target);
                 Self::mouse_button_slider(ui, "Fire Button", &mut config.fire_button, CaptureTarget::FireButton, &self.capture_target);
-                Self::mouse_button_slider(ui, "Macro Button", &mut config.macro_button, CaptureTarget::MacroButton, &self.capture_target);
-                
+                Self::activation_trigger_control(ui, "Macro Button", &mut config.macro_button.trigger, CaptureTarget::MacroButton, &self.capture_target);
+
                 ui.checkbox(&mut config.enable_macro_alt, "Enable Alternative Macro Button");
                 if config.enable_macro_alt {
-                    Self::mouse_button_slider(ui, "Alt Macro Button", &mut config.macro_alt_button, CaptureTarget::MacroAltButton, &self.capture_target);
+                    Self::activation_trigger_control(ui, "Alt Macro Button", &mut config.macro_alt_button.trigger, CaptureTarget::MacroAltButton, &self.capture_target);
+                }
+
+                ui.checkbox(&mut config.enable_rapid_click_alt, "Enable Alternative Rapid-Click Trigger");
+                if config.enable_rapid_click_alt {
+                    Self::activation_trigger_control(ui, "Alt Rapid-Click Trigger", &mut config.rapid_click_alt_trigger.trigger, CaptureTarget::RapidClickAltTrigger, &self.capture_target);
                 }
             });
-            
+
             ui.separator();
-            
+
+            // Recorded macro sequences - record a sequence of key/mouse events with their
+            // original timing, bind it to a trigger, and it replays alongside the built-in
+            // contagion sequence whenever that trigger fires.
+            ui.collapsing("🎬 Recorded Macros", |ui| {
+                ui.horizontal(|ui| {
+                    ui.label("New macro name:");
+                    ui.text_edit_singleline(&mut self.new_macro_name);
+                });
+
+                let is_recording = self.recording.lock().unwrap().is_some();
+                ui.horizontal(|ui| {
+                    if !is_recording {
+                        let can_start = !self.new_macro_name.trim().is_empty();
+                        if ui.add_enabled(can_start, egui::Button::new("⏺ Start Recording")).clicked() {
+                            *self.recording.lock().unwrap() = Some(RecordingSession::new());
+                        }
+                    } else if ui.button("⏹ Stop Recording").clicked() {
+                        if let Some(session) = self.recording.lock().unwrap().take() {
+                            let name = std::mem::take(&mut self.new_macro_name);
+                            config.macros.push(crate::recorder::NamedMacro::new(name, crate::recorder::Recording { events: session.finish() }));
+                        }
+                    }
+                });
+                if is_recording {
+                    ui.label(egui::RichText::new("⏺ Recording... press every key/mouse button you want captured, then Stop").color(egui::Color32::RED));
+                }
+
+                if !config.macros.is_empty() {
+                    ui.separator();
+                }
+                let mut remove_index: Option<usize> = None;
+                for i in 0..config.macros.len() {
+                    ui.horizontal(|ui| {
+                        ui.label(format!("{} ({} events)", config.macros[i].name, config.macros[i].recording.events.len()));
+                        ui.checkbox(&mut config.macros[i].scale_delays, "Scale with playback speed");
+                        if ui.button("🗑").clicked() {
+                            remove_index = Some(i);
+                        }
+                    });
+                    Self::activation_trigger_control(ui, "  Trigger", &mut config.macros[i].trigger.trigger, CaptureTarget::MacroSlot(i), &self.capture_target);
+                }
+                if let Some(i) = remove_index {
+                    config.macros.remove(i);
+                }
+            });
+
+            ui.separator();
+
             // Status and info
             let current_capture = *self.capture_target.lock().unwrap();
             if current_capture != CaptureTarget::None {
-                ui.label(egui::RichText::new("🎯 Listening for input... Press any key or mouse button (Escape to cancel)").color(egui::Color32::YELLOW));
+                ui.label(egui::RichText::new("🎯 Listening for input... Press any key, mouse button, or gamepad button (Escape to cancel)").color(egui::Color32::YELLOW));
                 #[cfg(target_os = "linux")]
                 ui.label(egui::RichText::new("💡 On Linux, side buttons are detected via evdev").color(egui::Color32::LIGHT_BLUE));
             } else {
@@ -573,10 +831,30 @@ impl eframe::App for MacroApp {
             }
             ui.label("Note: Close this window to exit the macro");
         });
+
+        // Persist any slider/keybind/profile mutation from this frame - compared against
+        // the last snapshot so the profile file is only rewritten when something actually
+        // changed, not on every repaint. The same dirty check also pushes the new config to
+        // the macro engine's worker, which otherwise has no way to know this frame's edits
+        // happened (it keeps its own owned snapshot rather than locking this Mutex itself).
+        let snapshot = self.config.lock().unwrap().clone();
+        let serialized = ron::ser::to_string(&snapshot).unwrap_or_default();
+        if serialized != self.last_saved_ron {
+            if let Err(e) = self.config.lock().unwrap().save_profile(&self.current_profile) {
+                eprintln!("Failed to save profile '{}': {}", self.current_profile, e);
+            }
+            let _ = self.command_tx.send(crate::WorkerCommand::UpdateConfig(Box::new(snapshot)));
+            self.last_saved_ron = serialized;
+        }
     }
 }
 
-pub fn run_gui(config: std::sync::Arc<std::sync::Mutex<SharedConfig>>) -> eframe::Result<()> {
+pub fn run_gui(
+    config: std::sync::Arc<std::sync::Mutex<SharedConfig>>,
+    profile: String,
+    capture_rx: std::sync::mpsc::Receiver<capture::CaptureEvent>,
+    command_tx: std::sync::mpsc::Sender<crate::WorkerCommand>,
+) -> eframe::Result<()> {
     let options = eframe::NativeOptions {
         viewport: egui::ViewportBuilder::default()
             .with_inner_size([500.0, 650.0])
@@ -584,14 +862,14 @@ pub fn run_gui(config: std::sync::Arc<std::sync::Mutex<SharedConfig>>) -> eframe
             .with_visible(true),
         ..Default::default()
     };
-    
+
     eprintln!("Initializing GUI window...");
     eframe::run_native(
         "Exodia Contagion Macro",
         options,
         Box::new(|_cc| {
             eprintln!("GUI window created successfully");
-            Box::new(MacroApp::new(config))
+            Box::new(MacroApp::new(config, profile, capture_rx, command_tx))
         }),
     )
 }
@@ -1,7 +1,14 @@
+mod capture;
 mod config;
 mod gui;
+mod output;
+mod recorder;
+mod sequence;
+mod window;
 
+use std::collections::HashSet;
 use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver, Sender};
 use std::sync::{Arc, Mutex};
 use std::thread;
 use std::time::{Duration, Instant};
@@ -9,10 +16,11 @@ use std::process;
 use std::env;
 
 use device_query::{DeviceQuery, DeviceState, Keycode};
-use enigo::{Enigo, Settings, Keyboard, Mouse};
-use sysinfo::System;
 
-use config::SharedConfig;
+use capture::CaptureEvent;
+use config::{held_modifiers, ActivationTrigger, Chord, GamepadTrigger, Hotkey, Modifiers, MouseTrigger, SharedConfig};
+use gilrs::{Gilrs, Axis as GilrsAxis, Button as GilrsButton};
+use output::OutputDevice;
 
 // ============================================================================
 // KEYBIND CONFIGURATION
@@ -21,16 +29,24 @@ use config::SharedConfig;
 // ============================================================================
 
 // Keybind Settings (internal structure, converted from SharedConfig)
-#[derive(Clone, Copy)]
+// Not Copy: Chord wraps an ActivationTrigger, which can carry a gamepad binding's owned name string.
+#[derive(Clone)]
 pub struct Keybinds {
-    pub melee: Keycode,
-    pub jump: Keycode,
+    pub melee: Hotkey,
+    pub jump: Hotkey,
     pub aim: usize, // Mouse button index
     pub fire: usize, // Mouse button index
-    pub emote: Keycode,
-    pub macro_button: usize, // Mouse button index
-    pub macro_alt: Option<usize>, // Mouse button index
-    pub rapid_click: Keycode,
+    pub emote: Hotkey,
+    pub macro_button: Chord,
+    pub macro_alt: Option<Chord>,
+    pub rapid_click: Hotkey,
+    pub rapid_click_alt: Option<Chord>,
+}
+
+// A hotkey "fires" when its base key is down and exactly its required modifiers are held -
+// no more, no less - so distinct macros can share a base key with different modifier sets.
+fn hotkey_matches(hotkey: &Hotkey, keys: &[Keycode], held_mods: Modifiers) -> bool {
+    keys.contains(&hotkey.code) && held_mods == hotkey.modifiers
 }
 
 // ============================================================================
@@ -47,7 +63,7 @@ pub struct Keybinds {
 struct State {
     running: AtomicBool,
     macro_enabled: AtomicBool,
-    warframe_active: AtomicBool,
+    target_active: AtomicBool,
     rapid_clicking: AtomicBool,
 }
 
@@ -56,15 +72,29 @@ impl State {
         Self {
             running: AtomicBool::new(false),
             macro_enabled: AtomicBool::new(true),
-            warframe_active: AtomicBool::new(false),
+            target_active: AtomicBool::new(false),
             rapid_clicking: AtomicBool::new(false),
         }
     }
 }
 
+// Pushed into the input loop's command channel by whoever owns a config change or wants to
+// steer a running macro - the GUI, or the background window-focus watcher - instead of that
+// loop re-locking and cloning SharedConfig on every poll (bottom's ThreadControlEvent is the
+// model here). The input loop keeps its own owned SharedConfig snapshot and only replaces it
+// when UpdateConfig arrives; Stop/ToggleEnabled/Reset just flip the relevant State atomics or
+// edge-detection latches. `state.running` itself stays a plain AtomicBool, since the rapid-fire
+// interpreter loop in sequence.rs polls it far too often to go through a channel.
+enum WorkerCommand {
+    UpdateConfig(Box<SharedConfig>),
+    Stop,
+    ToggleEnabled,
+    Reset,
+}
+
 // High-precision sleep using busy-wait for short durations
 #[inline(always)]
-fn precise_sleep(duration: Duration) {
+pub(crate) fn precise_sleep(duration: Duration) {
     if duration.is_zero() {
         return;
     }
@@ -86,116 +116,6 @@ fn precise_sleep(duration: Duration) {
     }
 }
 
-// Cached system for window detection (thread-local to avoid synchronization overhead)
-thread_local! {
-    static SYSTEM_CACHE: std::cell::RefCell<Option<System>> = std::cell::RefCell::new(None);
-}
-
-// Check if Warframe is the active window
-fn is_warframe_active() -> bool {
-    #[cfg(target_os = "linux")]
-    {
-        use std::process::Command;
-        // Try xdotool first (faster)
-        if let Ok(output) = Command::new("xdotool")
-            .arg("getactivewindow")
-            .arg("getwindowname")
-            .output()
-        {
-            if let Ok(name) = String::from_utf8(output.stdout) {
-                // Use case-insensitive check without allocation
-                return name.as_bytes().windows(8).any(|w| w.eq_ignore_ascii_case(b"warframe"));
-            }
-        }
-        
-        // Fallback: check process list (cached system)
-        SYSTEM_CACHE.with(|sys| {
-            let mut system = sys.borrow_mut();
-            if system.is_none() {
-                *system = Some(System::new());
-            }
-            if let Some(ref mut s) = *system {
-                s.refresh_all();
-                for process in s.processes().values() {
-                    if let Some(name) = process.name().to_str() {
-                        if name.as_bytes().windows(8).any(|w| w.eq_ignore_ascii_case(b"warframe")) {
-                            return true;
-                        }
-                    }
-                }
-            }
-            false
-        })
-    }
-    
-    #[cfg(target_os = "windows")]
-    {
-        use winapi::um::winuser::{GetForegroundWindow, GetWindowThreadProcessId};
-        use winapi::um::processthreadsapi::OpenProcess;
-        use winapi::um::winnt::PROCESS_QUERY_INFORMATION;
-        use winapi::um::handleapi::CloseHandle;
-        use std::ffi::CString;
-        use std::os::raw::c_char;
-        
-        unsafe {
-            let hwnd = GetForegroundWindow();
-            if hwnd.is_null() {
-                return false;
-            }
-            
-            let mut pid: u32 = 0;
-            GetWindowThreadProcessId(hwnd, &mut pid);
-            
-            if pid == 0 {
-                return false;
-            }
-            
-            let handle = OpenProcess(PROCESS_QUERY_INFORMATION, 0, pid);
-            if handle.is_null() {
-                return false;
-            }
-            
-            SYSTEM_CACHE.with(|sys| {
-                let mut system = sys.borrow_mut();
-                if system.is_none() {
-                    *system = Some(System::new());
-                }
-                if let Some(ref mut s) = *system {
-                    s.refresh_process(sysinfo::Pid::from_u32(pid));
-                    if let Some(process) = s.process(sysinfo::Pid::from_u32(pid)) {
-                        let name = process.name();
-                        // Use byte comparison for better performance
-                        let result = name.as_bytes().windows(8).any(|w| w.eq_ignore_ascii_case(b"warframe"));
-                        CloseHandle(handle);
-                        return result;
-                    }
-                }
-                CloseHandle(handle);
-                false
-            })
-        }
-    }
-    
-    #[cfg(target_os = "macos")]
-    {
-        use std::process::Command;
-        if let Ok(output) = Command::new("osascript")
-            .arg("-e")
-            .arg("tell application \"System Events\" to get name of first application process whose frontmost is true")
-            .output()
-        {
-            if let Ok(name) = String::from_utf8(output.stdout) {
-                return name.as_bytes().windows(8).any(|w| w.eq_ignore_ascii_case(b"warframe"));
-            }
-        }
-        false
-    }
-    
-    #[cfg(not(any(target_os = "linux", target_os = "windows", target_os = "macos")))]
-    {
-        true // Default to true for unknown platforms
-    }
-}
 
 // Set process to high priority
 fn set_high_priority() {
@@ -225,39 +145,6 @@ fn set_high_priority() {
     }
 }
 
-// Precomputed key mappings for performance
-#[derive(Clone, Copy)]
-struct PrecomputedKeys {
-    jump: enigo::Key,
-    melee: enigo::Key,
-    emote: enigo::Key,
-    #[allow(dead_code)]
-    rapid_click: enigo::Key, // Precomputed but not currently used (rapid click uses keycode directly)
-}
-
-impl PrecomputedKeys {
-    fn from_keybinds(keybinds: &Keybinds) -> Self {
-        Self {
-            jump: match keybinds.jump {
-                Keycode::Space => enigo::Key::Space,
-                _ => enigo::Key::Unicode(' '),
-            },
-            melee: match keybinds.melee {
-                Keycode::E => enigo::Key::Unicode('e'),
-                _ => enigo::Key::Unicode(' '),
-            },
-            emote: match keybinds.emote {
-                Keycode::Dot => enigo::Key::Unicode('.'),
-                _ => enigo::Key::Unicode(' '),
-            },
-            rapid_click: match keybinds.rapid_click {
-                Keycode::J => enigo::Key::Unicode('j'),
-                _ => enigo::Key::Unicode(' '),
-            },
-        }
-    }
-}
-
 // Button lookup table for O(1) access
 // device_query uses 0-based indexing: 1=Left, 2=Right, 3=Middle, 8=Side1, 9=Side2
 // This matches pynput's Button.button8 (index 8) and Button.button9 (index 9)
@@ -270,199 +157,291 @@ const BUTTON_LOOKUP: [Option<enigo::Button>; 10] = [
     None,                                    // 5
     None,                                    // 6
     None,                                    // 7
-    Some(enigo::Button::Left),              // 8 = Side button 1 (button8 in pynput) - using Left as fallback
-    Some(enigo::Button::Left),              // 9 = Side button 2 (button9 in pynput) - using Left as fallback
+    Some(enigo::Button::Left),              // 8 = Side button 1 (button8 in pynput) - see button_from_index
+    Some(enigo::Button::Left),              // 9 = Side button 2 (button9 in pynput) - see button_from_index
 ];
 
+// enigo has no Button variant for the side buttons, so OutputDevice::mouse_button tries a
+// backend that can actually emit them (uinput on Linux, XTEST, SendInput on Windows - see
+// output.rs) before ever calling this. This Left mapping only fires if every native backend
+// is unavailable, so a stray click still goes somewhere instead of silently doing nothing.
 #[inline(always)]
-fn button_from_index(idx: usize) -> enigo::Button {
-    // For side buttons (8, 9), we need to use a different approach since enigo might not have direct support
-    // We'll use the lookup for standard buttons, and for side buttons we'll need special handling
-    if idx == 8 {
-        // Side button 1 - try to use a workaround or map to available button
-        // Note: enigo might not support side buttons directly, so we may need to use xdotool on Linux
-        enigo::Button::Left // Fallback for now
-    } else if idx == 9 {
-        // Side button 2
-        enigo::Button::Left // Fallback for now
-    } else {
-        BUTTON_LOOKUP.get(idx).and_then(|&b| b).unwrap_or(enigo::Button::Left)
+pub(crate) fn button_from_index(idx: usize) -> enigo::Button {
+    BUTTON_LOOKUP.get(idx).and_then(|&b| b).unwrap_or(enigo::Button::Left)
+}
+
+// Scroll-wheel polling backend for the MouseTrigger::Scroll* triggers. device_query has no
+// wheel support, so on Linux we fall back to reading REL_WHEEL/REL_HWHEEL straight off an
+// evdev device (the same way capture.rs's evdev reader does); other platforms report no ticks.
+#[cfg(target_os = "linux")]
+type ScrollDevice = Option<evdev::Device>;
+#[cfg(not(target_os = "linux"))]
+type ScrollDevice = ();
+
+#[cfg(target_os = "linux")]
+fn open_scroll_device() -> ScrollDevice {
+    use std::fs;
+    use std::path::Path;
+    let input_dir = Path::new("/dev/input");
+    if let Ok(entries) = fs::read_dir(input_dir) {
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if let Some(name) = path.file_name().and_then(|n| n.to_str()) {
+                if name.starts_with("event") {
+                    if let Ok(device) = evdev::Device::open(&path) {
+                        let name_lower = device.name().unwrap_or_default().to_lowercase();
+                        if name_lower.contains("mouse") || name_lower.contains("pointer") {
+                            return Some(device);
+                        }
+                    }
+                }
+            }
+        }
     }
+    None
 }
+#[cfg(not(target_os = "linux"))]
+fn open_scroll_device() -> ScrollDevice {}
 
-// Helper functions to get durations from config
-fn get_durations_from_config(config: &SharedConfig) -> (Duration, Duration, Duration, Duration, Duration, Duration, Duration, Duration) {
-    (
-        config.double_jump_delay(),
-        Duration::from_millis(config.aim_melee_delay_ms),
-        Duration::from_millis(config.melee_hold_time_ms),
-        config.emote_preparation_delay(),
-        Duration::from_millis(config.rapid_fire_click_delay_ms),
-        Duration::from_millis(config.sequence_end_delay_ms),
-        Duration::from_millis(config.loop_delay_ms),
-        Duration::from_millis(config.rapid_click_delay_ms),
-    )
+// Net wheel ticks accumulated since the last call: vertical (positive = up) and
+// horizontal/tilt (positive = right), mirroring winit's MouseScrollDelta.
+#[derive(Clone, Copy, Default)]
+pub(crate) struct ScrollDelta {
+    pub vertical: i32,
+    pub horizontal: i32,
 }
 
-// Execute one complete Exodia Contagion sequence
-#[inline]
-fn execute_contagion_sequence(
-    enigo: &mut Enigo,
-    state: &State,
-    keys: &PrecomputedKeys,
-    aim_button: enigo::Button,
-    fire_button: enigo::Button,
-    config: &SharedConfig,
-) {
-    if !state.running.load(Ordering::Relaxed) {
-        return;
+#[cfg(target_os = "linux")]
+fn poll_scroll_delta(device: &mut ScrollDevice) -> ScrollDelta {
+    const EV_REL: u16 = 2;
+    const REL_HWHEEL: u16 = 6;
+    const REL_WHEEL: u16 = 8;
+    let mut delta = ScrollDelta::default();
+    if let Some(ref mut dev) = device {
+        if let Ok(events) = dev.fetch_events() {
+            for event in events {
+                if event.event_type().0 != EV_REL {
+                    continue;
+                }
+                match event.code() {
+                    REL_WHEEL => delta.vertical += event.value(),
+                    REL_HWHEEL => delta.horizontal += event.value(),
+                    _ => {}
+                }
+            }
+        }
     }
-    
-    let (double_jump_delay, aim_melee_delay, melee_hold_time, emote_prep_delay, 
-         rapid_fire_click_delay, sequence_end_delay, _, _) = get_durations_from_config(config);
-    
-    // Double jump
-    let _ = enigo.key(keys.jump, enigo::Direction::Press);
-    precise_sleep(double_jump_delay);
-    let _ = enigo.key(keys.jump, enigo::Direction::Release);
-    
-    let _ = enigo.key(keys.jump, enigo::Direction::Press);
-    precise_sleep(double_jump_delay);
-    let _ = enigo.key(keys.jump, enigo::Direction::Release);
-    
-    // Aim and melee
-    let _ = enigo.button(aim_button, enigo::Direction::Press);
-    precise_sleep(aim_melee_delay);
-    
-    let _ = enigo.key(keys.melee, enigo::Direction::Press);
-    precise_sleep(melee_hold_time);
-    let _ = enigo.key(keys.melee, enigo::Direction::Release);
-    
-    let _ = enigo.button(aim_button, enigo::Direction::Release);
-    
-    // Emote cancel
-    precise_sleep(emote_prep_delay);
-    
-    let _ = enigo.key(keys.emote, enigo::Direction::Press);
-    precise_sleep(double_jump_delay);
-    let _ = enigo.key(keys.emote, enigo::Direction::Release);
-    
-    let _ = enigo.key(keys.emote, enigo::Direction::Press);
-    precise_sleep(double_jump_delay);
-    let _ = enigo.key(keys.emote, enigo::Direction::Release);
-    
-    // Rapid fire - optimized loop
-    let start_time = Instant::now();
-    let duration_limit = config.rapid_fire_duration_ms as u128;
-    
-    while state.running.load(Ordering::Relaxed) {
-        let _ = enigo.button(fire_button, enigo::Direction::Press);
-        let _ = enigo.button(fire_button, enigo::Direction::Release);
-        precise_sleep(rapid_fire_click_delay);
-        
-        // Check elapsed time less frequently for better performance
-        if start_time.elapsed().as_millis() > duration_limit {
-            break;
+    delta
+}
+#[cfg(not(target_os = "linux"))]
+fn poll_scroll_delta(_device: &mut ScrollDevice) -> ScrollDelta {
+    ScrollDelta::default()
+}
+
+// Resolve whether a MouseTrigger is currently active: a plain button press, a scroll-wheel
+// tick since the last poll (vertical or horizontal/tilt), or a bound button held while the
+// pointer has moved (drag).
+fn mouse_trigger_active(
+    trigger: MouseTrigger,
+    button_pressed: &[bool],
+    scroll_delta: ScrollDelta,
+    coords: (i32, i32),
+    last_coords: (i32, i32),
+) -> bool {
+    match trigger {
+        MouseTrigger::Button(idx) => button_pressed.get(idx).copied().unwrap_or(false),
+        MouseTrigger::ScrollUp => scroll_delta.vertical > 0,
+        MouseTrigger::ScrollDown => scroll_delta.vertical < 0,
+        MouseTrigger::ScrollLeft => scroll_delta.horizontal < 0,
+        MouseTrigger::ScrollRight => scroll_delta.horizontal > 0,
+        MouseTrigger::Drag(idx) => {
+            button_pressed.get(idx).copied().unwrap_or(false) && coords != last_coords
         }
     }
-    
-    // End-of-sequence delay
-    if state.running.load(Ordering::Relaxed) {
-        precise_sleep(sequence_end_delay);
+}
+
+// Map the button/axis name half of a GamepadTrigger's tagged string back to gilrs's own
+// enum, mirroring the capture side in gui.rs (which stores `format!("{:?}", button)`).
+fn gamepad_button_from_name(name: &str) -> Option<GilrsButton> {
+    use GilrsButton::*;
+    Some(match name {
+        "South" => South,
+        "East" => East,
+        "North" => North,
+        "West" => West,
+        "C" => C,
+        "Z" => Z,
+        "LeftTrigger" => LeftTrigger,
+        "LeftTrigger2" => LeftTrigger2,
+        "RightTrigger" => RightTrigger,
+        "RightTrigger2" => RightTrigger2,
+        "Select" => Select,
+        "Start" => Start,
+        "Mode" => Mode,
+        "LeftThumb" => LeftThumb,
+        "RightThumb" => RightThumb,
+        "DPadUp" => DPadUp,
+        "DPadDown" => DPadDown,
+        "DPadLeft" => DPadLeft,
+        "DPadRight" => DPadRight,
+        _ => return None,
+    })
+}
+
+fn gamepad_axis_from_name(name: &str) -> Option<GilrsAxis> {
+    use GilrsAxis::*;
+    Some(match name {
+        "LeftStickX" => LeftStickX,
+        "LeftStickY" => LeftStickY,
+        "LeftZ" => LeftZ,
+        "RightStickX" => RightStickX,
+        "RightStickY" => RightStickY,
+        "RightZ" => RightZ,
+        "DPadX" => DPadX,
+        "DPadY" => DPadY,
+        _ => return None,
+    })
+}
+
+// Resolve whether a GamepadTrigger is currently active on any connected controller.
+fn gamepad_trigger_active(trigger: &GamepadTrigger, gilrs: &Gilrs) -> bool {
+    match trigger {
+        GamepadTrigger::Button(name) => match gamepad_button_from_name(name) {
+            Some(button) => gilrs.gamepads().any(|(_, pad)| pad.is_pressed(button)),
+            None => false,
+        },
+        GamepadTrigger::AxisAbove(name, threshold) => match gamepad_axis_from_name(name) {
+            Some(axis) => gilrs.gamepads().any(|(_, pad)| {
+                pad.axis_data(axis).map_or(false, |data| data.value().abs() >= *threshold)
+            }),
+            None => false,
+        },
     }
 }
 
-// Main loop that executes contagion sequences while key is held
-fn contagion_loop(state: Arc<State>, config: Arc<Mutex<SharedConfig>>) {
-    let settings = Settings::default();
-    let mut enigo = match Enigo::new(&settings) {
-        Ok(e) => e,
-        Err(_) => return,
-    };
-    
+// Resolve an ActivationTrigger against whichever device backs it (mouse or gamepad).
+// `gilrs` is None when controller support failed to initialize, in which case any
+// gamepad-bound trigger is simply treated as never active.
+fn activation_trigger_active(
+    trigger: &ActivationTrigger,
+    button_pressed: &[bool],
+    scroll_delta: ScrollDelta,
+    coords: (i32, i32),
+    last_coords: (i32, i32),
+    gilrs: Option<&Gilrs>,
+) -> bool {
+    match trigger {
+        ActivationTrigger::Mouse(m) => mouse_trigger_active(*m, button_pressed, scroll_delta, coords, last_coords),
+        ActivationTrigger::Gamepad(g) => gilrs.map_or(false, |gilrs| gamepad_trigger_active(g, gilrs)),
+    }
+}
+
+// A Chord "fires" when its underlying trigger is active and exactly its required
+// modifiers are held - the ActivationTrigger counterpart of hotkey_matches, so a
+// mouse/gamepad-bound macro can require e.g. Ctrl the same way a keyboard hotkey can.
+fn chord_active(
+    chord: &Chord,
+    held_mods: Modifiers,
+    button_pressed: &[bool],
+    scroll_delta: ScrollDelta,
+    coords: (i32, i32),
+    last_coords: (i32, i32),
+    gilrs: Option<&Gilrs>,
+) -> bool {
+    held_mods == chord.modifiers
+        && activation_trigger_active(&chord.trigger, button_pressed, scroll_delta, coords, last_coords, gilrs)
+}
+
+// Main loop that runs the configured macro sequence (the built-in Contagion routine, or a
+// user-authored one) while the trigger is held. The sequence itself - what used to be
+// `execute_contagion_sequence`'s fixed imperative routine - now lives in sequence.rs as an
+// interpreted `Vec<MacroStep>`, so this is just the repeat-until-released wrapper around it.
+// `config` is an owned snapshot handed over by the input loop at the moment this activated -
+// no more per-iteration lock/clone here, since the caller already keeps its own up-to-date
+// copy driven by WorkerCommand::UpdateConfig.
+fn contagion_loop(state: Arc<State>, config: SharedConfig) {
+    let mut output = OutputDevice::new();
+    let mut held = sequence::HeldState::default();
+
+    // Resolved once per activation rather than per iteration: `config` is a fixed snapshot
+    // for this whole activation, so the step list (a custom sequence file, or the built-in
+    // routine with its timing fields baked in) can't change while the trigger is held. Only
+    // loop_delay_ms is read from `config` inside the loop below.
+    let steps = sequence::load_sequence(&config);
+
     while state.running.load(Ordering::Relaxed) {
-        // Get current config snapshot
-        let config_snapshot = config.lock().unwrap().clone();
-        let keybinds = config_snapshot.to_keybinds();
-        
-        // Precompute everything once per iteration
-        let keys = PrecomputedKeys::from_keybinds(&keybinds);
-        let fire_button = button_from_index(keybinds.fire);
-        let aim_button = button_from_index(keybinds.aim);
-        
-        execute_contagion_sequence(&mut enigo, &state, &keys, aim_button, fire_button, &config_snapshot);
-        
-        let (_, _, _, _, _, _, loop_delay, _) = get_durations_from_config(&config_snapshot);
-        precise_sleep(loop_delay);
+        sequence::run_sequence(&mut output, &state, &steps, &mut held);
+        precise_sleep(Duration::from_millis(config.loop_delay_ms));
     }
-    
-    // Cleanup: release all keys/buttons
-    let config_snapshot = config.lock().unwrap().clone();
-    let keybinds = config_snapshot.to_keybinds();
-    let keys = PrecomputedKeys::from_keybinds(&keybinds);
-    let fire_button = button_from_index(keybinds.fire);
-    let aim_button = button_from_index(keybinds.aim);
-    
-    let _ = enigo.key(keys.melee, enigo::Direction::Release);
-    let _ = enigo.key(keys.emote, enigo::Direction::Release);
-    let _ = enigo.button(aim_button, enigo::Direction::Release);
-    let _ = enigo.button(fire_button, enigo::Direction::Release);
+
+    // Release whatever the sequence left held, rather than a fixed set of four keys/buttons.
+    held.release_all(&mut output);
 }
 
-// Execute rapid click sequence
-fn execute_rapid_click(state: Arc<State>, config: Arc<Mutex<SharedConfig>>) {
+// Execute rapid click sequence - same owned-snapshot handoff as contagion_loop above.
+fn execute_rapid_click(state: Arc<State>, config: SharedConfig) {
     state.rapid_clicking.store(true, Ordering::Relaxed);
-    let settings = Settings::default();
-    let mut enigo = match Enigo::new(&settings) {
-        Ok(e) => e,
-        Err(_) => {
-            state.rapid_clicking.store(false, Ordering::Relaxed);
-            return;
-        }
-    };
-    
-    let config_snapshot = config.lock().unwrap().clone();
-    let keybinds = config_snapshot.to_keybinds();
-    let fire_button = button_from_index(keybinds.fire);
-    let rapid_click_delay = Duration::from_millis(config_snapshot.rapid_click_delay_ms);
-    
-    for _ in 0..config_snapshot.rapid_click_count {
+    let mut output = OutputDevice::new();
+
+    let keybinds = config.to_keybinds();
+    let rapid_click_delay = Duration::from_millis(config.rapid_click_delay_ms);
+
+    for _ in 0..config.rapid_click_count {
         if !state.macro_enabled.load(Ordering::Relaxed) {
             break;
         }
-        
-        let _ = enigo.button(fire_button, enigo::Direction::Press);
-        let _ = enigo.button(fire_button, enigo::Direction::Release);
+
+        output.mouse_button(keybinds.fire, enigo::Direction::Press);
+        output.mouse_button(keybinds.fire, enigo::Direction::Release);
         precise_sleep(rapid_click_delay);
     }
-    
+
     state.rapid_clicking.store(false, Ordering::Relaxed);
 }
 
-// Background thread to monitor Warframe window state
-fn background_app_check(state: Arc<State>) {
+// Background thread to monitor the target window's focus state. Focus loss is reported to
+// the input loop as commands (Stop the running macro, Reset its edge-detection latches)
+// instead of reaching across into `state`/latches directly, so every external "stop this
+// macro" request goes through the same WorkerCommand path as a GUI-driven config change.
+// `target` is the configurable substring to match against the active window's title/process
+// name (SharedConfig::target_window) - this is what let this stop being hardcoded to
+// "warframe" and started letting the tool target any game.
+fn background_app_check(state: Arc<State>, command_tx: Sender<WorkerCommand>, target: String) {
+    let mut watcher = window::select_watcher();
     let mut last_state = false;
     loop {
-        let current_state = is_warframe_active();
-        
+        let current_state = watcher.active_window_matches(&target);
+
         // Only update and print if state changed
         if current_state != last_state {
-            state.warframe_active.store(current_state, Ordering::Relaxed);
-            if !current_state && state.running.load(Ordering::Relaxed) {
-                state.running.store(false, Ordering::Relaxed);
-                println!("Warframe window lost focus - macro stopped");
+            state.target_active.store(current_state, Ordering::Relaxed);
+            if !current_state {
+                if state.running.load(Ordering::Relaxed) {
+                    let _ = command_tx.send(WorkerCommand::Stop);
+                    println!("Target window lost focus - macro stopped");
+                }
+                let _ = command_tx.send(WorkerCommand::Reset);
             }
             last_state = current_state;
         } else {
             // If state unchanged, just update atomic (cheaper)
-            state.warframe_active.store(current_state, Ordering::Relaxed);
+            state.target_active.store(current_state, Ordering::Relaxed);
         }
-        
-        thread::sleep(Duration::from_secs(1));
+
+        // Block until the backend reports a focus change (or the fallback timeout elapses
+        // for backends with no cheap event source) instead of polling on a fixed interval
+        // regardless of whether anything actually changed.
+        watcher.wait_for_change(Duration::from_secs(1));
     }
 }
 
-fn run_macro(config: Arc<Mutex<SharedConfig>>, state: Arc<State>) {
+fn run_macro(
+    config: Arc<Mutex<SharedConfig>>,
+    state: Arc<State>,
+    capture_rx: Receiver<CaptureEvent>,
+    command_tx: Sender<WorkerCommand>,
+    command_rx: Receiver<WorkerCommand>,
+) {
     println!("=== Exodia Contagion Macro for Warframe (Rust - Optimized) ===");
     let config_snapshot = config.lock().unwrap().clone();
     println!("\nKEY SETTINGS:");
@@ -470,69 +449,154 @@ fn run_macro(config: Arc<Mutex<SharedConfig>>, state: Arc<State>) {
     println!("  - Press '{}' to perform {} rapid clicks", config_snapshot.rapid_click_key, config_snapshot.rapid_click_count);
     println!("  - Press F11 to toggle all macros on/off");
     println!("\nPress Ctrl+C to exit\n");
-    
+
     // Start background window monitoring
     let state_clone = Arc::clone(&state);
+    let command_tx_bg = command_tx.clone();
+    let target_window = config_snapshot.target_window.clone();
     thread::spawn(move || {
-        background_app_check(state_clone);
+        background_app_check(state_clone, command_tx_bg, target_window);
     });
-    
+
     // Input monitoring loop
     let state_input = Arc::clone(&state);
     let config_input = Arc::clone(&config);
+    let command_tx_input = command_tx.clone();
     thread::spawn(move || {
         let device_state = DeviceState::new();
         let mut last_macro_state = false;
         let mut last_rapid_click_state = false;
-        
+        let mut last_rapid_click_alt_state = false;
+        let mut named_macro_states: Vec<bool> = Vec::new();
+        let mut scroll_device = open_scroll_device();
+        let mut last_coords = device_state.get_mouse().coords;
+        let mut gilrs = match Gilrs::new() {
+            Ok(g) => Some(g),
+            Err(e) => {
+                eprintln!("Gamepad support unavailable ({}), gamepad triggers will be ignored", e);
+                None
+            }
+        };
+
+        // Key/button state is driven by the shared capture channel (so it reflects evdev's
+        // global view, not just this process's focused window); only the pointer position,
+        // which CaptureEvent doesn't carry, still comes from device_query.
+        let mut held_keys: HashSet<Keycode> = HashSet::new();
+        let mut mouse_pressed: Vec<bool> = Vec::new();
+
+        // Owned, locally-updated config snapshot - seeded once here instead of re-locking
+        // and cloning the shared Mutex on every poll of this loop. It's only ever replaced
+        // when a WorkerCommand::UpdateConfig arrives below.
+        let mut config_snapshot = config_input.lock().unwrap().clone();
+
         loop {
-            let keys = device_state.get_keys();
-            let mouse = device_state.get_mouse();
-            
-            // Get current config
-            let config_snapshot = config_input.lock().unwrap().clone();
+            while let Ok(command) = command_rx.try_recv() {
+                match command {
+                    WorkerCommand::UpdateConfig(new_config) => {
+                        config_snapshot = *new_config;
+                    }
+                    WorkerCommand::Stop => {
+                        state_input.running.store(false, Ordering::Relaxed);
+                    }
+                    WorkerCommand::ToggleEnabled => {
+                        let current = state_input.macro_enabled.load(Ordering::Relaxed);
+                        state_input.macro_enabled.store(!current, Ordering::Relaxed);
+                        println!("Macro {}", if !current { "enabled" } else { "disabled" });
+                    }
+                    WorkerCommand::Reset => {
+                        last_macro_state = false;
+                        last_rapid_click_state = false;
+                        last_rapid_click_alt_state = false;
+                        for s in named_macro_states.iter_mut() {
+                            *s = false;
+                        }
+                    }
+                }
+            }
+
+            while let Ok(event) = capture_rx.try_recv() {
+                match event {
+                    CaptureEvent::KeyDown(k) => {
+                        held_keys.insert(k);
+                    }
+                    CaptureEvent::KeyUp(k) => {
+                        held_keys.remove(&k);
+                    }
+                    CaptureEvent::MouseDown(idx) => {
+                        if mouse_pressed.len() <= idx {
+                            mouse_pressed.resize(idx + 1, false);
+                        }
+                        mouse_pressed[idx] = true;
+                    }
+                    CaptureEvent::MouseUp(idx) => {
+                        if mouse_pressed.len() <= idx {
+                            mouse_pressed.resize(idx + 1, false);
+                        }
+                        mouse_pressed[idx] = false;
+                    }
+                    CaptureEvent::Scroll(_) => {} // handled separately by poll_scroll_delta below
+                }
+            }
+            let keys: Vec<Keycode> = held_keys.iter().cloned().collect();
+            let coords = device_state.get_mouse().coords;
+            let scroll_delta = poll_scroll_delta(&mut scroll_device);
+            if let Some(ref mut g) = gilrs {
+                while g.next_event().is_some() {}
+            }
+
             let keybinds = config_snapshot.to_keybinds();
-            
-            // Check for F11 toggle
+
+            // Check for F11 toggle - routed through the command channel like every other
+            // control signal, rather than flipping the atomic inline here.
             if keys.contains(&Keycode::F11) {
-                let current = state_input.macro_enabled.load(Ordering::Relaxed);
-                state_input.macro_enabled.store(!current, Ordering::Relaxed);
-                println!("Macro {}", if !current { "enabled" } else { "disabled" });
+                let _ = command_tx_input.send(WorkerCommand::ToggleEnabled);
                 thread::sleep(Duration::from_millis(200)); // Debounce
             }
             
-            // Only process macro inputs if Warframe is active (to avoid interfering with GUI)
-            let warframe_active = state_input.warframe_active.load(Ordering::Relaxed);
+            // Only process macro inputs if the target window is active (to avoid interfering with GUI)
+            let target_active = state_input.target_active.load(Ordering::Relaxed);
             
-            if warframe_active {
-                // Check for rapid click key
-                let rapid_click_pressed = keys.contains(&keybinds.rapid_click);
-                if rapid_click_pressed && !last_rapid_click_state 
+            if target_active {
+                // Check for rapid click key (base key down AND exactly its required modifiers held)
+                let held_mods = held_modifiers(&keys);
+                let rapid_click_pressed = hotkey_matches(&keybinds.rapid_click, &keys, held_mods);
+                if rapid_click_pressed && !last_rapid_click_state
                     && state_input.macro_enabled.load(Ordering::Relaxed) {
                     let state_clone = Arc::clone(&state_input);
-                    let config_clone = Arc::clone(&config_input);
+                    let config_clone = config_snapshot.clone();
                     thread::spawn(move || {
                         execute_rapid_click(state_clone, config_clone);
                     });
                 }
                 last_rapid_click_state = rapid_click_pressed;
-                
-                // Check for macro button
-                let macro_button_idx = keybinds.macro_button;
-                let macro_pressed = macro_button_idx < mouse.button_pressed.len() 
-                    && mouse.button_pressed[macro_button_idx]
-                    || (keybinds.macro_alt.is_some() 
-                        && {
-                            let alt_idx = keybinds.macro_alt.unwrap();
-                            alt_idx < mouse.button_pressed.len() && mouse.button_pressed[alt_idx]
-                        });
+
+                // Alternative rapid-click trigger (mouse scroll/button or gamepad), alongside
+                // the keyboard hotkey above - fires the same action, just from a different input.
+                let rapid_click_alt_pressed = keybinds.rapid_click_alt.as_ref().map_or(false, |alt| {
+                    chord_active(alt, held_mods, &mouse_pressed, scroll_delta, coords, last_coords, gilrs.as_ref())
+                });
+                if rapid_click_alt_pressed && !last_rapid_click_alt_state
+                    && state_input.macro_enabled.load(Ordering::Relaxed) {
+                    let state_clone = Arc::clone(&state_input);
+                    let config_clone = config_snapshot.clone();
+                    thread::spawn(move || {
+                        execute_rapid_click(state_clone, config_clone);
+                    });
+                }
+                last_rapid_click_alt_state = rapid_click_alt_pressed;
+
+                // Check for macro button (mouse button/scroll/drag, or a gamepad binding)
+                let macro_pressed = chord_active(&keybinds.macro_button, held_mods, &mouse_pressed, scroll_delta, coords, last_coords, gilrs.as_ref())
+                    || keybinds.macro_alt.as_ref().map_or(false, |alt| {
+                        chord_active(alt, held_mods, &mouse_pressed, scroll_delta, coords, last_coords, gilrs.as_ref())
+                    });
                 
                 if macro_pressed && !last_macro_state 
                     && !state_input.running.load(Ordering::Relaxed)
                     && state_input.macro_enabled.load(Ordering::Relaxed) {
                     state_input.running.store(true, Ordering::Relaxed);
                     let state_clone = Arc::clone(&state_input);
-                    let config_clone = Arc::clone(&config_input);
+                    let config_clone = config_snapshot.clone();
                     thread::spawn(move || {
                         contagion_loop(state_clone, config_clone);
                     });
@@ -540,12 +604,34 @@ fn run_macro(config: Arc<Mutex<SharedConfig>>, state: Arc<State>) {
                     state_input.running.store(false, Ordering::Relaxed);
                 }
                 last_macro_state = macro_pressed;
+
+                // Named macros: same edge-triggered activation as the built-in macro button,
+                // but each one plays back its own recorded sequence instead of the fixed
+                // contagion routine.
+                named_macro_states.resize(config_snapshot.macros.len(), false);
+                for (i, named_macro) in config_snapshot.macros.iter().enumerate() {
+                    let pressed = chord_active(&named_macro.trigger, held_mods, &mouse_pressed, scroll_delta, coords, last_coords, gilrs.as_ref());
+                    if pressed && !named_macro_states[i] && state_input.macro_enabled.load(Ordering::Relaxed) {
+                        let macro_clone = named_macro.clone();
+                        let speed = config_snapshot.playback_speed;
+                        thread::spawn(move || {
+                            macro_clone.play(speed);
+                        });
+                    }
+                    named_macro_states[i] = pressed;
+                }
             } else {
-                // Reset states when Warframe is not active to avoid stuck states
+                // Reset states when the target window is not active to avoid stuck states
                 last_rapid_click_state = false;
+                last_rapid_click_alt_state = false;
                 last_macro_state = false;
+                for s in named_macro_states.iter_mut() {
+                    *s = false;
+                }
             }
             
+            last_coords = coords;
+
             // Adaptive polling: faster when active, slower when idle
             let sleep_duration = if state_input.running.load(Ordering::Relaxed) {
                 Duration::from_micros(500) // 0.5ms when macro is running
@@ -576,28 +662,46 @@ fn main() {
     let args: Vec<String> = env::args().collect();
     let use_gui = args.iter().any(|arg| arg == "--gui" || arg == "-g");
     
-    let config = Arc::new(Mutex::new(SharedConfig::default()));
+    let profile_name = config::DEFAULT_PROFILE.to_string();
+    let loaded_config = SharedConfig::load_profile(&profile_name).unwrap_or_else(|e| {
+        eprintln!("Failed to load profile '{}': {} - using defaults", profile_name, e);
+        SharedConfig::default()
+    });
+    let config = Arc::new(Mutex::new(loaded_config));
     let state = Arc::new(State::new());
-    
+
+    // One command channel, shared by every sender that wants to push a config update or
+    // steer a running macro: the GUI (config edits), in --gui mode, run_macro's own
+    // background window-watcher, and the input loop itself (F11 toggle). The single receiver
+    // end lives on the input loop thread.
+    let (command_tx, command_rx) = mpsc::channel::<WorkerCommand>();
+
     if use_gui {
         println!("Starting GUI mode...");
         println!("Note: Keybind configuration works independently - Warframe does not need to be open");
-        
+
+        // One capture backend, fanned out to two receivers: the macro engine's trigger
+        // polling and the GUI's keybind capture both see every event, so binding a key
+        // can complete even while the game window (not the config window) has focus.
+        let mut capture_rxs = capture::spawn_n(2);
+        let gui_capture_rx = capture_rxs.pop().unwrap();
+        let macro_capture_rx = capture_rxs.pop().unwrap();
+
         // Start macro in background (but it will only activate when Warframe is open)
         let config_macro = Arc::clone(&config);
         let state_macro = Arc::clone(&state);
-        
+        let command_tx_macro = command_tx.clone();
+
         thread::spawn(move || {
-            run_macro(config_macro, state_macro);
+            run_macro(config_macro, state_macro, macro_capture_rx, command_tx_macro, command_rx);
         });
-        
+
         // Small delay to let macro thread start
         thread::sleep(Duration::from_millis(100));
-        
+
         // Run GUI (blocks until window closed)
-        // The GUI has its own independent key capture thread that works regardless of Warframe
         println!("Opening GUI window...");
-        match gui::run_gui(config) {
+        match gui::run_gui(config, profile_name, gui_capture_rx, command_tx) {
             Ok(()) => println!("GUI closed normally"),
             Err(e) => {
                 eprintln!("GUI Error: {}", e);
@@ -609,8 +713,12 @@ fn main() {
             }
         }
     } else {
-        // CLI mode - just run the macro
-        run_macro(config, state);
+        // CLI mode - just run the macro, with its own dedicated capture receiver. Nothing
+        // sends UpdateConfig here (there's no GUI to push config edits; only the window
+        // watcher's Stop/Reset and the F11 toggle arrive), so the input loop's snapshot just
+        // keeps the profile it loaded at startup, same as before.
+        let macro_capture_rx = capture::spawn();
+        run_macro(config, state, macro_capture_rx, command_tx, command_rx);
     }
 }
 
@@ -0,0 +1,322 @@
+// Synthetic input output - the mirror image of capture.rs, which reads real
+// /dev/input/event* devices directly. On Linux this instead writes to a persistent uinput
+// virtual device (the same approach xremap's output_device uses), so macro key presses and
+// side-button clicks keep working under Wayland, where device_query/xdotool-based injection
+// doesn't. Every other platform keeps using enigo, same as before this existed, except for
+// the Windows side-button path (SendInput) below.
+use enigo::{Direction, Enigo, Key, Settings};
+
+// A way to press/release a mouse button index that enigo itself can't express - the extra
+// side buttons (8/9), which enigo has no Button variant for. Each platform gets its own
+// native implementation; `OutputDevice::mouse_button` only falls through to one of these
+// for those indices, everything else still goes through enigo as before.
+pub trait MouseBackend {
+    fn press(&mut self, button_idx: usize) -> bool;
+    fn release(&mut self, button_idx: usize) -> bool;
+}
+
+pub struct OutputDevice {
+    enigo: Enigo,
+    #[cfg(target_os = "linux")]
+    uinput: Option<evdev::uinput::VirtualDevice>,
+    #[cfg(target_os = "linux")]
+    xtest: Option<xtest::XTestBackend>,
+    #[cfg(target_os = "windows")]
+    sendinput: win_mouse::SendInputBackend,
+}
+
+impl OutputDevice {
+    pub fn new() -> Self {
+        let enigo = Enigo::new(&Settings::default()).expect("failed to initialize enigo");
+        #[cfg(target_os = "linux")]
+        let uinput = match linux::build_device() {
+            Ok(device) => Some(device),
+            Err(e) => {
+                eprintln!("Could not create uinput output device ({}), falling back to enigo for synthetic input", e);
+                None
+            }
+        };
+        #[cfg(target_os = "linux")]
+        let xtest = xtest::XTestBackend::new();
+
+        Self {
+            enigo,
+            #[cfg(target_os = "linux")]
+            uinput,
+            #[cfg(target_os = "linux")]
+            xtest,
+            #[cfg(target_os = "windows")]
+            sendinput: win_mouse::SendInputBackend::new(),
+        }
+    }
+
+    // Press/release a keyboard key - melee, jump, emote and rapid-click all route through this.
+    pub fn key(&mut self, key: Key, direction: Direction) {
+        if direction == Direction::Click {
+            self.key(key, Direction::Press);
+            self.key(key, Direction::Release);
+            return;
+        }
+
+        #[cfg(target_os = "linux")]
+        if let Some(ref mut device) = self.uinput {
+            if linux::send_key(device, key, direction).is_ok() {
+                return;
+            }
+        }
+        let _ = self.enigo.key(key, direction);
+    }
+
+    // Press/release a mouse button by the same index the capture backend and device_query
+    // use (1 = Left, 8/9 for the side buttons), so the output side emits the exact
+    // BTN_SIDE/BTN_EXTRA codes instead of falling back to Left like button_from_index does.
+    pub fn mouse_button(&mut self, idx: usize, direction: Direction) {
+        if direction == Direction::Click {
+            self.mouse_button(idx, Direction::Press);
+            self.mouse_button(idx, Direction::Release);
+            return;
+        }
+
+        #[cfg(target_os = "linux")]
+        if let Some(ref mut device) = self.uinput {
+            if linux::send_button(device, idx, direction).is_ok() {
+                return;
+            }
+        }
+
+        // enigo has no Button variant for the side buttons, so button_from_index maps
+        // both of them to Left - try a backend that can actually emit XButton1/XButton2
+        // first, and only let that mapping kick in as the very last resort.
+        if idx == 8 || idx == 9 {
+            #[cfg(target_os = "linux")]
+            if let Some(ref mut backend) = self.xtest {
+                let sent = match direction {
+                    Direction::Press => backend.press(idx),
+                    Direction::Release => backend.release(idx),
+                    Direction::Click => unreachable!("Click is expanded to Press+Release above"),
+                };
+                if sent {
+                    return;
+                }
+            }
+            #[cfg(target_os = "windows")]
+            {
+                let sent = match direction {
+                    Direction::Press => self.sendinput.press(idx),
+                    Direction::Release => self.sendinput.release(idx),
+                    Direction::Click => unreachable!("Click is expanded to Press+Release above"),
+                };
+                if sent {
+                    return;
+                }
+            }
+        }
+
+        let _ = self.enigo.button(crate::button_from_index(idx), direction);
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod linux {
+    use enigo::{Direction, Key};
+    use evdev::uinput::{VirtualDevice, VirtualDeviceBuilder};
+    use evdev::{AttributeSet, EventType, InputEvent, Key as EvKey, RelativeAxisType};
+    use std::io;
+
+    // Registers KEY_* codes 1-247 (the keyboard block, which covers every key key_to_code
+    // can emit; higher KEY_* codes are not registered) plus the mouse button set and
+    // REL_X/REL_Y/REL_WHEEL for movement/scroll.
+    pub fn build_device() -> io::Result<VirtualDevice> {
+        let mut keys = AttributeSet::<EvKey>::new();
+        for code in 1u16..248 {
+            keys.insert(EvKey::new(code));
+        }
+        for code in [0x110u16, 0x111, 0x112, 0x113, 0x114] {
+            keys.insert(EvKey::new(code));
+        }
+
+        let mut axes = AttributeSet::<RelativeAxisType>::new();
+        axes.insert(RelativeAxisType::REL_X);
+        axes.insert(RelativeAxisType::REL_Y);
+        axes.insert(RelativeAxisType::REL_WHEEL);
+
+        VirtualDeviceBuilder::new()?
+            .name("rustodia-output")
+            .with_keys(&keys)?
+            .with_relative_axes(&axes)?
+            .build()
+    }
+
+    pub fn send_key(device: &mut VirtualDevice, key: Key, direction: Direction) -> io::Result<()> {
+        let code = key_to_code(key).ok_or_else(|| io::Error::new(io::ErrorKind::Unsupported, "unmapped key"))?;
+        emit(device, code, direction)
+    }
+
+    pub fn send_button(device: &mut VirtualDevice, idx: usize, direction: Direction) -> io::Result<()> {
+        let code = match idx {
+            1 => 0x110, // BTN_LEFT
+            2 => 0x111, // BTN_RIGHT
+            3 => 0x112, // BTN_MIDDLE
+            8 => 0x113, // BTN_SIDE
+            9 => 0x114, // BTN_EXTRA
+            _ => return Err(io::Error::new(io::ErrorKind::Unsupported, "unmapped mouse button")),
+        };
+        emit(device, code, direction)
+    }
+
+    fn emit(device: &mut VirtualDevice, code: u16, direction: Direction) -> io::Result<()> {
+        let value = match direction {
+            Direction::Press => 1,
+            Direction::Release => 0,
+            Direction::Click => unreachable!("Click is expanded to Press+Release before reaching the backend"),
+        };
+        device.emit(&[InputEvent::new(EventType::KEY, code, value)])
+    }
+
+    // Letters, digits, Space and Dot, the subset of capture.rs's translate_key table that
+    // sequence::keycode_to_enigo_key can produce, just the other direction. Anything else
+    // (F-keys included) returns None, and OutputDevice::key falls back to enigo.
+    fn key_to_code(key: Key) -> Option<u16> {
+        Some(match key {
+            Key::Space => 57,
+            Key::Unicode(c) => match c.to_ascii_uppercase() {
+                'A' => 30, 'B' => 48, 'C' => 46, 'D' => 32, 'E' => 18, 'F' => 33, 'G' => 34,
+                'H' => 35, 'I' => 23, 'J' => 36, 'K' => 37, 'L' => 38, 'M' => 50, 'N' => 49,
+                'O' => 24, 'P' => 25, 'Q' => 16, 'R' => 19, 'S' => 31, 'T' => 20, 'U' => 22,
+                'V' => 47, 'W' => 17, 'X' => 45, 'Y' => 21, 'Z' => 44,
+                '0' => 11, '1' => 2, '2' => 3, '3' => 4, '4' => 5, '5' => 6, '6' => 7, '7' => 8,
+                '8' => 9, '9' => 10,
+                '.' => 52,
+                ' ' => 57,
+                _ => return None,
+            },
+            _ => return None,
+        })
+    }
+}
+
+// XTEST-based injection for the side buttons, since the Enigo/uinput paths above can't
+// express them: uinput only exists when we could create the virtual device in the first
+// place, and plain X11 clients (as opposed to this process's own uinput device) still need
+// a way to fake input on displays where uinput access isn't available. XTEST works against
+// any X server without needing a virtual device or root, which is why it's the fallback
+// here rather than the primary path.
+#[cfg(target_os = "linux")]
+mod xtest {
+    use super::MouseBackend;
+    use xcb::x;
+    use xcb::xtest;
+
+    pub struct XTestBackend {
+        conn: xcb::Connection,
+    }
+
+    impl XTestBackend {
+        // Returns None rather than erroring out - most test boxes and headless/Wayland
+        // sessions have no X server to connect to, and that's fine, since the enigo
+        // fallback in OutputDevice::mouse_button still covers those cases.
+        pub fn new() -> Option<Self> {
+            let (conn, _) = xcb::Connection::connect(None).ok()?;
+            Some(Self { conn })
+        }
+
+        fn send(&mut self, detail: u8, press: bool) -> bool {
+            let event_type = if press {
+                xtest::FAKE_BUTTON_PRESS
+            } else {
+                xtest::FAKE_BUTTON_RELEASE
+            };
+            let cookie = self.conn.send_request_checked(&xtest::FakeInput {
+                r#type: event_type as u8,
+                detail,
+                time: x::CURRENT_TIME,
+                root: x::WINDOW_NONE,
+                root_x: 0,
+                root_y: 0,
+                deviceid: 0,
+            });
+            self.conn.check_request(cookie).is_ok() && self.conn.flush().is_ok()
+        }
+
+        // XTEST button numbers follow X's own 1-based convention (1/2/3 for left/middle/right,
+        // 8/9 for the side buttons). Only 8/9 coincide with this macro's indices (which use
+        // 2 = Right, 3 = Middle), and only those two are mapped here.
+        fn button_code(button_idx: usize) -> Option<u8> {
+            match button_idx {
+                8 => Some(8),
+                9 => Some(9),
+                _ => None,
+            }
+        }
+    }
+
+    impl MouseBackend for XTestBackend {
+        fn press(&mut self, button_idx: usize) -> bool {
+            match Self::button_code(button_idx) {
+                Some(code) => self.send(code, true),
+                None => false,
+            }
+        }
+
+        fn release(&mut self, button_idx: usize) -> bool {
+            match Self::button_code(button_idx) {
+                Some(code) => self.send(code, false),
+                None => false,
+            }
+        }
+    }
+}
+
+// SendInput-based injection for the side buttons on Windows - enigo's Button enum still
+// has no XButton1/XButton2 variant, so this is the only way to emit them short of a
+// kernel-mode driver.
+#[cfg(target_os = "windows")]
+mod win_mouse {
+    use super::MouseBackend;
+    use winapi::um::winuser::{
+        SendInput, INPUT, INPUT_MOUSE, MOUSEEVENTF_XDOWN, MOUSEEVENTF_XUP, MOUSEINPUT, XBUTTON1, XBUTTON2,
+    };
+    use std::mem::size_of;
+
+    #[derive(Default)]
+    pub struct SendInputBackend;
+
+    impl SendInputBackend {
+        pub fn new() -> Self {
+            Self
+        }
+
+        fn send(&mut self, button_idx: usize, flag: u32) -> bool {
+            let mouse_data = match button_idx {
+                8 => XBUTTON1,
+                9 => XBUTTON2,
+                _ => return false,
+            };
+
+            let mut input: INPUT = unsafe { std::mem::zeroed() };
+            input.type_ = INPUT_MOUSE;
+            let mi = unsafe { input.u.mi_mut() };
+            *mi = MOUSEINPUT {
+                dx: 0,
+                dy: 0,
+                mouseData: mouse_data as u32,
+                dwFlags: flag,
+                time: 0,
+                dwExtraInfo: 0,
+            };
+
+            let sent = unsafe { SendInput(1, &mut input, size_of::<INPUT>() as i32) };
+            sent == 1
+        }
+    }
+
+    impl MouseBackend for SendInputBackend {
+        fn press(&mut self, button_idx: usize) -> bool {
+            self.send(button_idx, MOUSEEVENTF_XDOWN)
+        }
+
+        fn release(&mut self, button_idx: usize) -> bool {
+            self.send(button_idx, MOUSEEVENTF_XUP)
+        }
+    }
+}
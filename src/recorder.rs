@@ -0,0 +1,133 @@
+// Capture and replay an arbitrary action sequence, rather than only running the
+// fixed melee/jump/emote routines the built-in Contagion macro plays (see sequence.rs).
+// Capture itself happens in gui.rs's RecordingSession, off the same evdev-backed capture
+// channel the GUI already uses for keybind capture (see capture.rs) - that's non-lossy and
+// event-driven, unlike a device_query polling loop would be, so this module only owns the
+// recorded data and its playback.
+use device_query::Keycode;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::time::Duration;
+
+use crate::config::{Chord, ConfigResult, SharedConfig};
+use crate::output::OutputDevice;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub enum EventKind {
+    Press,
+    Release,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Serialize, Deserialize)]
+pub enum Target {
+    Key(#[serde(with = "keycode_serde")] Keycode),
+    MouseButton(usize),
+}
+
+// Recorded inter-event gap in milliseconds (stored as millis rather than Duration so the
+// struct round-trips through serde/TOML the same way the rest of SharedConfig's timings do).
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct Event {
+    pub kind: EventKind,
+    pub target: Target,
+    pub delay_since_prev_ms: u64,
+}
+
+#[derive(Clone, Default, Debug, Serialize, Deserialize)]
+pub struct Recording {
+    pub events: Vec<Event>,
+}
+
+impl Recording {
+    pub fn load_from_path(path: &Path) -> ConfigResult<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let contents = std::fs::read_to_string(path)?;
+        let recording = toml::from_str(&contents)?;
+        Ok(recording)
+    }
+
+    pub fn save_to_path(&self, path: &Path) -> ConfigResult<()> {
+        let contents = toml::to_string_pretty(self)?;
+        std::fs::write(path, contents)?;
+        Ok(())
+    }
+
+    // Walk the recorded events, sleeping the captured gap (scaled by `speed`, the caller's
+    // playback-speed multiplier - NamedMacro::play resolves this from SharedConfig's global
+    // playback_speed or the macro's own scale_delays flag) and synthesizing the corresponding
+    // key/mouse input. No layout parameter: Target::Key stores the physical Keycode captured,
+    // and crate::sequence::keycode_to_enigo_key always resolves that against Qwerty, same as
+    // keycode_serde already does when saving/loading - a recording is portable across the
+    // replaying machine's own layout setting.
+    pub fn play_with_speed(&self, speed: f64) {
+        let mut output = OutputDevice::new();
+        let speed = if speed > 0.0 { speed } else { 1.0 };
+
+        for event in &self.events {
+            let scaled_delay = Duration::from_secs_f64(event.delay_since_prev_ms as f64 / 1000.0 / speed);
+            crate::precise_sleep(scaled_delay);
+
+            let direction = match event.kind {
+                EventKind::Press => enigo::Direction::Press,
+                EventKind::Release => enigo::Direction::Release,
+            };
+            match event.target {
+                Target::Key(code) => {
+                    output.key(crate::sequence::keycode_to_enigo_key(code), direction);
+                }
+                Target::MouseButton(idx) => {
+                    output.mouse_button(idx, direction);
+                }
+            }
+        }
+    }
+}
+
+// A saved, named macro: a captured event sequence plus the trigger that replays it and
+// whether it should stretch/compress with the global playback_speed slider or always
+// play back at its originally recorded pace.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct NamedMacro {
+    pub name: String,
+    pub trigger: Chord,
+    pub scale_delays: bool,
+    pub recording: Recording,
+}
+
+impl NamedMacro {
+    pub fn new(name: String, recording: Recording) -> Self {
+        Self {
+            name,
+            trigger: Chord::default(),
+            scale_delays: true,
+            recording,
+        }
+    }
+
+    pub fn play(&self, global_speed: f64) {
+        let speed = if self.scale_delays { global_speed } else { 1.0 };
+        self.recording.play_with_speed(speed);
+    }
+}
+
+// Serde helper: Keycode isn't serde-aware, so round-trip it through SharedConfig's
+// existing human-readable string conversion (e.g. "E", "Space"). Recordings are keyed
+// to the physical key, not a printed character, so they stay portable across layouts -
+// always encode/decode against Qwerty regardless of the recording user's own layout.
+mod keycode_serde {
+    use device_query::Keycode;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    use crate::config::{KeyboardLayout, SharedConfig};
+
+    pub fn serialize<S: Serializer>(code: &Keycode, serializer: S) -> Result<S::Ok, S::Error> {
+        SharedConfig::keycode_to_string(*code, KeyboardLayout::Qwerty).serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Keycode, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        Ok(SharedConfig::keycode_from_string(&s, KeyboardLayout::Qwerty))
+    }
+}
@@ -0,0 +1,218 @@
+// A small action-list interpreter, generalizing the fixed Exodia Contagion routine that
+// used to be hardcoded in main.rs's `execute_contagion_sequence` into an ordered list of
+// steps anyone can author - modeled on enigo's own press/release primitives, with
+// Sleep/RepeatFor/RepeatWhileHeld covering the little control flow a weapon combo needs.
+use device_query::Keycode;
+use enigo::Direction;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::path::Path;
+use std::sync::atomic::Ordering;
+use std::time::{Duration, Instant};
+
+use crate::config::{ConfigError, ConfigResult, SharedConfig};
+use crate::output::OutputDevice;
+use crate::State;
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub enum MacroStep {
+    KeyPress(#[serde(with = "keycode_serde")] Keycode),
+    KeyRelease(#[serde(with = "keycode_serde")] Keycode),
+    // Press, hold for `hold_ms`, then release - the shape every tap in the old Contagion
+    // routine actually had (double-jump, melee, emote cancel).
+    KeyTap(#[serde(with = "keycode_serde")] Keycode, u64),
+    MouseDown(usize),
+    MouseUp(usize),
+    Click(usize),
+    Sleep(u64),
+    RepeatFor { ms: u64, body: Vec<MacroStep> },
+    // Loops its body for as long as `state.running` stays true - the rapid-fire tail of
+    // Contagion uses `RepeatFor` instead, since it has its own fixed duration, but a
+    // hand-authored combo can use this to keep firing for as long as the trigger is held.
+    RepeatWhileHeld { body: Vec<MacroStep> },
+}
+
+// A saved, shareable action list - the TOML/JSON-on-disk counterpart of `Vec<MacroStep>`,
+// the same way `recorder::Recording` wraps `Vec<Event>` so it round-trips through serde.
+#[derive(Clone, Default, Debug, Serialize, Deserialize)]
+pub struct MacroSequence {
+    pub steps: Vec<MacroStep>,
+}
+
+impl MacroSequence {
+    pub fn load_from_path(path: &Path) -> ConfigResult<Self> {
+        let contents = std::fs::read_to_string(path)?;
+        if path.extension().and_then(|e| e.to_str()) == Some("json") {
+            Ok(serde_json::from_str(&contents)?)
+        } else {
+            Ok(toml::from_str(&contents)?)
+        }
+    }
+
+    pub fn save_to_path(&self, path: &Path) -> ConfigResult<()> {
+        let contents = if path.extension().and_then(|e| e.to_str()) == Some("json") {
+            serde_json::to_string_pretty(self)?
+        } else {
+            toml::to_string_pretty(self)?
+        };
+        std::fs::write(path, contents)?;
+        Ok(())
+    }
+}
+
+// Resolve the step list a running macro should actually play: a user-authored sequence
+// file if one is configured and loads cleanly, otherwise the built-in Contagion routine
+// regenerated from the profile's current timing fields (so the fps-derived delay formulas
+// still apply, the same way they did before this existed).
+pub fn load_sequence(config: &SharedConfig) -> Vec<MacroStep> {
+    if let Some(path) = &config.custom_sequence_path {
+        match MacroSequence::load_from_path(Path::new(path)) {
+            Ok(sequence) => return sequence.steps,
+            Err(e) => eprintln!(
+                "Failed to load macro sequence '{}': {} - using the built-in Contagion routine",
+                path, e
+            ),
+        }
+    }
+    build_contagion_sequence(config)
+}
+
+// The original Exodia Contagion combo (double jump, aim+melee, emote cancel, rapid fire),
+// expressed as a step list instead of imperative code - double_jump_delay/
+// emote_preparation_delay still come from the live formulas, so the tuned timing carries
+// over. MacroStep's delays are u64 milliseconds like every other delay_ms field on
+// SharedConfig, so these two get rounded down to the millisecond via as_millis() same as
+// they would be stored in an editable sequence file - sub-millisecond differences the old
+// precise_sleep(Duration) call preserved are lost, but they were never user-adjustable.
+pub fn build_contagion_sequence(config: &SharedConfig) -> Vec<MacroStep> {
+    let keybinds = config.to_keybinds();
+    let double_jump_ms = config.double_jump_delay().as_millis() as u64;
+    let emote_prep_ms = config.emote_preparation_delay().as_millis() as u64;
+
+    vec![
+        MacroStep::KeyTap(keybinds.jump.code, double_jump_ms),
+        MacroStep::KeyTap(keybinds.jump.code, double_jump_ms),
+        MacroStep::MouseDown(keybinds.aim),
+        MacroStep::Sleep(config.aim_melee_delay_ms),
+        MacroStep::KeyTap(keybinds.melee.code, config.melee_hold_time_ms),
+        MacroStep::MouseUp(keybinds.aim),
+        MacroStep::Sleep(emote_prep_ms),
+        MacroStep::KeyTap(keybinds.emote.code, double_jump_ms),
+        MacroStep::KeyTap(keybinds.emote.code, double_jump_ms),
+        MacroStep::RepeatFor {
+            ms: config.rapid_fire_duration_ms,
+            body: vec![
+                MacroStep::Click(keybinds.fire),
+                MacroStep::Sleep(config.rapid_fire_click_delay_ms),
+            ],
+        },
+        MacroStep::Sleep(config.sequence_end_delay_ms),
+    ]
+}
+
+// Every key/button a running sequence currently holds down, so an abort mid-sequence (or
+// the worker thread exiting) can release exactly what's pressed instead of a fixed list of
+// four keys.
+#[derive(Default)]
+pub struct HeldState {
+    keys: HashSet<Keycode>,
+    buttons: HashSet<usize>,
+}
+
+impl HeldState {
+    pub fn release_all(&mut self, output: &mut OutputDevice) {
+        for key in self.keys.drain() {
+            output.key(keycode_to_enigo_key(key), Direction::Release);
+        }
+        for idx in self.buttons.drain() {
+            output.mouse_button(idx, Direction::Release);
+        }
+    }
+}
+
+// Walk a step list, consulting `state.running` between (and inside repeated) steps so a
+// sequence aborts as soon as the macro is toggled off, the same way the old fixed loop did.
+pub fn run_sequence(output: &mut OutputDevice, state: &State, steps: &[MacroStep], held: &mut HeldState) {
+    for step in steps {
+        if !state.running.load(Ordering::Relaxed) {
+            return;
+        }
+        match step {
+            MacroStep::KeyPress(code) => {
+                output.key(keycode_to_enigo_key(*code), Direction::Press);
+                held.keys.insert(*code);
+            }
+            MacroStep::KeyRelease(code) => {
+                output.key(keycode_to_enigo_key(*code), Direction::Release);
+                held.keys.remove(code);
+            }
+            MacroStep::KeyTap(code, hold_ms) => {
+                output.key(keycode_to_enigo_key(*code), Direction::Press);
+                crate::precise_sleep(Duration::from_millis(*hold_ms));
+                output.key(keycode_to_enigo_key(*code), Direction::Release);
+            }
+            MacroStep::MouseDown(idx) => {
+                output.mouse_button(*idx, Direction::Press);
+                held.buttons.insert(*idx);
+            }
+            MacroStep::MouseUp(idx) => {
+                output.mouse_button(*idx, Direction::Release);
+                held.buttons.remove(idx);
+            }
+            MacroStep::Click(idx) => {
+                output.mouse_button(*idx, Direction::Press);
+                output.mouse_button(*idx, Direction::Release);
+            }
+            MacroStep::Sleep(ms) => {
+                crate::precise_sleep(Duration::from_millis(*ms));
+            }
+            MacroStep::RepeatFor { ms, body } => {
+                let start = Instant::now();
+                while state.running.load(Ordering::Relaxed) && start.elapsed().as_millis() < *ms as u128 {
+                    run_sequence(output, state, body, held);
+                }
+            }
+            MacroStep::RepeatWhileHeld { body } => {
+                while state.running.load(Ordering::Relaxed) {
+                    run_sequence(output, state, body, held);
+                }
+            }
+        }
+    }
+}
+
+// Generalizes what `PrecomputedKeys::from_keybinds` used to do for four fixed keys: resolve
+// any physical Keycode this macro supports (letters, digits, F-keys, Space, Dot - see
+// SharedConfig::physical_keycode_from_string) to the enigo key that types it. Multi-character
+// labels (F1, F11, ...) aren't synthesizable through enigo's Unicode key yet, so they fall
+// back to Space the same way the old per-key match did for anything unexpected.
+pub fn keycode_to_enigo_key(code: Keycode) -> enigo::Key {
+    if code == Keycode::Space {
+        return enigo::Key::Space;
+    }
+    let label = SharedConfig::keycode_to_string(code, crate::config::KeyboardLayout::Qwerty);
+    let mut chars = label.chars();
+    match (chars.next(), chars.next()) {
+        (Some(c), None) => enigo::Key::Unicode(c.to_ascii_lowercase()),
+        _ => enigo::Key::Unicode(' '),
+    }
+}
+
+// Serde helper: Keycode isn't serde-aware, so round-trip it through the same human-readable
+// string conversion recorder.rs's recordings use (e.g. "E", "Space"), always against Qwerty
+// so a sequence file stays portable across the author's keyboard layout setting.
+mod keycode_serde {
+    use device_query::Keycode;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    use crate::config::{KeyboardLayout, SharedConfig};
+
+    pub fn serialize<S: Serializer>(code: &Keycode, serializer: S) -> Result<S::Ok, S::Error> {
+        SharedConfig::keycode_to_string(*code, KeyboardLayout::Qwerty).serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Keycode, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        Ok(SharedConfig::keycode_from_string(&s, KeyboardLayout::Qwerty))
+    }
+}